@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use libsrt_sys as srt;
+use srt_rs::error::{handle_result, SrtError, SrtErrorKind, SrtRejectReason};
+
+use std::io;
+
+// Codes as libsrt could hand them back, including ones newer libsrt versions
+// may add.
+fuzz_target!(|codes: (i32, u32, i32)| {
+    let (errno, reject, return_code) = codes;
+    let e = SrtError::from(SrtErrorKind::from(srt::SRT_ERRNO(errno)));
+    let _ = e.to_string();
+    let _ = io::Error::from(e).kind();
+    let reason = SrtRejectReason::from(srt::SRT_REJECT_REASON(reject));
+    let _ = SrtErrorKind::ConnRej(reason).to_string();
+    if return_code != -1 {
+        assert_eq!(handle_result((), return_code).is_ok(), return_code == 0);
+    }
+});
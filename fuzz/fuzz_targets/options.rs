@@ -0,0 +1,100 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use srt_rs::{self as srt, PacketFilter, Passphrase, SrtContext, SrtCryptoMode};
+
+use std::{mem, sync::Once};
+
+#[derive(Arbitrary, Debug)]
+enum Opt {
+    ConnectionTimeout(i32),
+    FlightFlagSize(i32),
+    IpTypeOfService(i32),
+    Ipv4TimeToLive(i32),
+    Ipv6Only(i32),
+    KmRefreshRate(i32),
+    KmPreannounce(i32),
+    Linger(i32),
+    MaxReorderTolerance(i32),
+    MaxBandwidth(i64),
+    MessageApi(bool),
+    MinVersion(i32),
+    Mss(i32),
+    NakReport(bool),
+    PacketFilter(String),
+    Passphrase(String),
+    PayloadSize(i32),
+    CryptoMode(u8),
+    EncryptionKeyLength(i32),
+    PeerIdleTimeout(i32),
+    PeerLatency(i32),
+    ReceiveBuffer(i32),
+    ReceiveLatency(i32),
+    SendBuffer(i32),
+    SendDropDelay(i32),
+    StreamId(String),
+    EnforcedEncryption(bool),
+    TooLatePacketDrop(bool),
+    FileTransmissionType,
+    UdpSendBuffer(i32),
+    UdpReceiveBuffer(i32),
+}
+
+static START: Once = Once::new();
+
+// Any combination of values must fail with an error, never a crash.
+fuzz_target!(|opts: Vec<Opt>| {
+    // Keeps libsrt up instead of starting it for every socket.
+    START.call_once(|| mem::forget(SrtContext::new().expect("fail SrtContext::new()")));
+    let mut builder = srt::builder();
+    for opt in opts {
+        builder = match opt {
+            Opt::ConnectionTimeout(v) => builder.set_connection_timeout(v),
+            Opt::FlightFlagSize(v) => builder.set_flight_flag_size(v),
+            Opt::IpTypeOfService(v) => builder.set_ip_type_of_service(v),
+            Opt::Ipv4TimeToLive(v) => builder.set_ipv4_time_to_live(v),
+            Opt::Ipv6Only(v) => builder.set_ipv6_only(v),
+            Opt::KmRefreshRate(v) => builder.set_km_refresh_rate(v),
+            Opt::KmPreannounce(v) => builder.set_km_preannounce(v),
+            Opt::Linger(v) => builder.set_linger(v),
+            Opt::MaxReorderTolerance(v) => builder.set_max_reorder_tolerance(v),
+            Opt::MaxBandwidth(v) => builder.set_max_bandwith(v),
+            Opt::MessageApi(v) => builder.set_message_api(v),
+            Opt::MinVersion(v) => builder.set_min_version(v),
+            Opt::Mss(v) => builder.set_mss(v),
+            Opt::NakReport(v) => builder.set_nak_report(v),
+            Opt::PacketFilter(v) => match v.parse::<PacketFilter>() {
+                Ok(filter) => builder.set_packet_filter(filter),
+                Err(_) => builder,
+            },
+            Opt::Passphrase(v) => match Passphrase::new(v) {
+                Ok(passphrase) => builder.set_passphrase(passphrase),
+                Err(_) => builder,
+            },
+            Opt::PayloadSize(v) => builder.set_payload_size(v),
+            Opt::CryptoMode(v) => builder.set_crypto_mode(match v % 3 {
+                0 => SrtCryptoMode::Auto,
+                1 => SrtCryptoMode::AesCtr,
+                _ => SrtCryptoMode::AesGcm,
+            }),
+            Opt::EncryptionKeyLength(v) => builder.set_encryption_key_length(v),
+            Opt::PeerIdleTimeout(v) => builder.set_peer_idle_timeout(v),
+            Opt::PeerLatency(v) => builder.set_peer_latency(v),
+            Opt::ReceiveBuffer(v) => builder.set_receive_buffer(v),
+            Opt::ReceiveLatency(v) => builder.set_receive_latency(v),
+            Opt::SendBuffer(v) => builder.set_send_buffer(v),
+            Opt::SendDropDelay(v) => builder.set_send_drop_delay(v),
+            Opt::StreamId(v) => builder.set_stream_id(v),
+            Opt::EnforcedEncryption(v) => builder.set_enforced_encryption(v),
+            Opt::TooLatePacketDrop(v) => builder.set_too_late_packet_drop(v),
+            Opt::FileTransmissionType => builder.set_file_transmission_type(),
+            Opt::UdpSendBuffer(v) => builder.set_udp_send_buffer(v),
+            Opt::UdpReceiveBuffer(v) => builder.set_udp_receive_buffer(v),
+        };
+    }
+    if let Ok(listener) = builder.listen("127.0.0.1:0", 1) {
+        let _ = listener.local_addr();
+        let _ = listener.close();
+    }
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use srt_rs::PacketFilter;
+
+fuzz_target!(|config: &str| {
+    if let Ok(filter) = config.parse::<PacketFilter>() {
+        assert_eq!(filter.to_string().parse::<PacketFilter>(), Ok(filter));
+    }
+});
@@ -0,0 +1,14 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use srt_rs::relay::{StreamId, StreamMode};
+
+fuzz_target!(|id: &str| {
+    if let Ok(parsed) = id.parse::<StreamId>() {
+        // Anything without the access control prefix is just a resource.
+        if !id.starts_with("#!::") {
+            assert_eq!(parsed.resource, id);
+            assert_eq!(parsed.mode, StreamMode::Request);
+        }
+    }
+});
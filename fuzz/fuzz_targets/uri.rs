@@ -0,0 +1,16 @@
+#![no_main]
+
+// The URIs of the srt-rs command line relay.
+#[allow(dead_code)]
+#[path = "../../src/bin/srt-rs/uri.rs"]
+mod uri;
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    if let Ok(uri) = uri::Uri::parse(text) {
+        let _ = uri.to_string();
+        let _ = uri.option::<u64>("latency");
+        let _ = uri.option::<String>("streamid");
+    }
+});
@@ -1,11 +1,189 @@
+#[cfg(feature = "bindgen")]
 use bindgen;
+#[cfg(feature = "vendored")]
 use cmake;
 
-use std::{env, path::Path, path::PathBuf};
+use std::env;
+use std::path::PathBuf;
+#[cfg(feature = "vendored")]
+use std::path::Path;
+
+// Oldest libsrt whose API matches the bindings this crate is written against.
+const MIN_SRT_VERSION: &str = "1.5.2";
+
+// Headers to generate bindings from, and whether libsrt was built with
+// ENABLE_AEAD_API_PREVIEW, without which it has no SRTO_CRYPTOMODE.
+struct Libsrt {
+    include_paths: Vec<PathBuf>,
+    aead_api: bool,
+}
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-env-changed=LIBSRT_SYS_USE_PKG_CONFIG");
+    println!("cargo:rerun-if-env-changed=LIBSRT_SYS_AEAD_API");
+    let libsrt = libsrt()?;
+    println!("cargo:rustc-check-cfg=cfg(srt_aead_api)");
+    if libsrt.aead_api {
+        println!("cargo:rustc-cfg=srt_aead_api");
+    }
+    generate_bindings(&libsrt.include_paths);
+    Ok(())
+}
+
+// Without the `bindgen` feature the checked-in src/bindings.rs is used.
+#[cfg(not(feature = "bindgen"))]
+fn generate_bindings(_include_paths: &[PathBuf]) {}
+
+#[cfg(feature = "bindgen")]
+fn generate_bindings(include_paths: &[PathBuf]) {
+    // Tell cargo to invalidate the built crate whenever the wrapper changes
+    println!("cargo:rerun-if-changed=wrapper.h");
+
+    // The bindgen::Builder is the main entry point
+    // to bindgen, and lets you build up options for
+    // the resulting bindings.
+    let bindings = bindgen::Builder::default()
+        // The input header we would like to generate
+        // bindings for.
+        .header("wrapper.h")
+        .clang_args(
+            include_paths
+                .iter()
+                .map(|path| format!("--include-directory={}", path.display())),
+        )
+        .size_t_is_usize(true)
+        .whitelist_function("srt_.*")
+        .whitelist_type("SRT.*")
+        .whitelist_var("SRT.*")
+        .bitfield_enum("SRT_EPOLL_OPT")
+        .default_enum_style(bindgen::EnumVariation::NewType { is_bitfield: false })
+        // Tell cargo to invalidate the built crate whenever any of the
+        // included header files changed.
+        .parse_callbacks(Box::new(bindgen::CargoCallbacks))
+        // Finish the builder and generate the bindings.
+        .generate()
+        // Unwrap the Result and panic on failure.
+        .expect("Unable to generate bindings");
+
+    // Write the bindings to the $OUT_DIR/bindings.rs file.
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+    bindings
+        .write_to_file(out_path.join("bindings.rs"))
+        .expect("Couldn't write bindings!");
+}
+
+// Setting LIBSRT_SYS_USE_PKG_CONFIG links the system libsrt even when the
+// `vendored` feature was enabled by some other crate in the dependency graph.
+#[cfg(feature = "vendored")]
+fn libsrt() -> Result<Libsrt, Box<dyn std::error::Error>> {
+    if env::var_os("LIBSRT_SYS_USE_PKG_CONFIG").is_some() {
+        system_libsrt()
+    } else {
+        vendored_libsrt()
+    }
+}
+
+#[cfg(not(feature = "vendored"))]
+fn libsrt() -> Result<Libsrt, Box<dyn std::error::Error>> {
+    system_libsrt()
+}
+
+fn system_libsrt() -> Result<Libsrt, Box<dyn std::error::Error>> {
+    let library = pkg_config::Config::new()
+        .atleast_version(MIN_SRT_VERSION)
+        .statik(cfg!(feature = "static"))
+        .probe("srt")
+        .map_err(|e| format!("libsrt >= {} not found by pkg-config: {}", MIN_SRT_VERSION, e))?;
+    // Installed headers live in <prefix>/include/srt, but not every srt.pc
+    // lists that directory in its Cflags.
+    let include_paths = library
+        .include_paths
+        .iter()
+        .flat_map(|path| vec![path.clone(), path.join("srt")])
+        .collect();
+    // srt.pc does not normally say how libsrt was configured, so setting
+    // LIBSRT_SYS_AEAD_API vouches for a system libsrt with the AEAD API.
+    let aead_api = library.defines.contains_key("ENABLE_AEAD_API_PREVIEW")
+        || env::var_os("LIBSRT_SYS_AEAD_API").is_some();
+    Ok(Libsrt {
+        include_paths,
+        aead_api,
+    })
+}
+
+// Cargo feature, whether it is enabled, the USE_ENCLIB value and the
+// libraries the vendored build then needs on the link line. OpenSSL is linked
+// through openssl-sys instead.
+#[cfg(feature = "vendored")]
+type CryptoBackend = (&'static str, bool, Option<&'static str>, &'static [&'static str]);
+
+#[cfg(feature = "vendored")]
+const CRYPTO_BACKENDS: [CryptoBackend; 5] = [
+    ("crypto-openssl", cfg!(feature = "crypto-openssl"), Some("openssl"), &[]),
+    ("crypto-openssl-evp", cfg!(feature = "crypto-openssl-evp"), Some("openssl-evp"), &[]),
+    (
+        "crypto-gnutls",
+        cfg!(feature = "crypto-gnutls"),
+        Some("gnutls"),
+        &["gnutls", "nettle", "hogweed", "gmp"],
+    ),
+    (
+        "crypto-mbedtls",
+        cfg!(feature = "crypto-mbedtls"),
+        Some("mbedtls"),
+        &["mbedtls", "mbedx509", "mbedcrypto"],
+    ),
+    ("crypto-none", cfg!(feature = "crypto-none"), None, &[]),
+];
+
+#[cfg(feature = "vendored")]
+fn crypto_backend() -> Result<&'static CryptoBackend, String> {
+    let enabled = CRYPTO_BACKENDS
+        .iter()
+        .filter(|(_, enabled, _, _)| *enabled)
+        .collect::<Vec<_>>();
+    match enabled[..] {
+        [backend] => Ok(backend),
+        [] => Err(format!(
+            "the vendored libsrt needs one crypto backend feature, one of: {}",
+            feature_names(CRYPTO_BACKENDS.iter())
+        )),
+        _ => Err(format!(
+            "crypto backend features are mutually exclusive, got: {}",
+            feature_names(enabled.into_iter())
+        )),
+    }
+}
+
+#[cfg(feature = "vendored")]
+fn feature_names<'a>(backends: impl Iterator<Item = &'a CryptoBackend>) -> String {
+    backends
+        .map(|(feature, _, _, _)| *feature)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(feature = "vendored")]
+fn config_crypto(cfg: &mut cmake::Config, enclib: Option<&str>) {
+    match enclib {
+        Some(enclib) => {
+            cfg.define("ENABLE_ENCRYPTION", "ON");
+            cfg.define("USE_ENCLIB", enclib);
+            // Exposes SRTO_CRYPTOMODE, matching the define in wrapper.h
+            cfg.define("ENABLE_AEAD_API_PREVIEW", "ON");
+        }
+        None => {
+            cfg.define("ENABLE_ENCRYPTION", "OFF");
+        }
+    }
+}
+
+#[cfg(feature = "vendored")]
+fn vendored_libsrt() -> Result<Libsrt, Box<dyn std::error::Error>> {
+    let (_, _, enclib, crypto_libs) = crypto_backend()?;
     if cfg!(unix) {
         let mut cfg = cmake::Config::new("libsrt");
+        config_crypto(&mut cfg, *enclib);
         cfg.define("ENABLE_APPS", "OFF");
         cfg.define("ENABLE_BONDING", "ON");
         cfg.define("CMAKE_POLICY_VERSION_MINIMUM", "3.5");
@@ -30,7 +208,9 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
         println!("cargo:rustc-link-lib=static=srt");
     } else if cfg!(windows) {
-        let dst = cmake::Config::new("libsrt")
+        let mut cfg = cmake::Config::new("libsrt");
+        config_crypto(&mut cfg, *enclib);
+        let dst = cfg
             .generator("Visual Studio 16 2019")
             .cxxflag("/EHs")
             .define("ENABLE_STDCXX_SYNC", "ON")
@@ -44,40 +224,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("cargo:rustc-link-search={}", bin_dir.display());
         println!("cargo:rustc-link-lib=srt");
     }
+    for lib in crypto_libs.iter() {
+        println!("cargo:rustc-link-lib={}", lib);
+    }
 
     let mut include_path = PathBuf::from(env::var("OUT_DIR").unwrap());
     include_path.push("include");
     include_path.push("srt");
-
-    // Tell cargo to invalidate the built crate whenever the wrapper changes
-    println!("cargo:rerun-if-changed=wrapper.h");
-
-    // The bindgen::Builder is the main entry point
-    // to bindgen, and lets you build up options for
-    // the resulting bindings.
-    let bindings = bindgen::Builder::default()
-        // The input header we would like to generate
-        // bindings for.
-        .header("wrapper.h")
-        .clang_arg(format!("--include-directory={}", include_path.display()))
-        .size_t_is_usize(true)
-        .whitelist_function("srt_.*")
-        .whitelist_type("SRT.*")
-        .whitelist_var("SRT.*")
-        .bitfield_enum("SRT_EPOLL_OPT")
-        .default_enum_style(bindgen::EnumVariation::NewType { is_bitfield: false })
-        // Tell cargo to invalidate the built crate whenever any of the
-        // included header files changed.
-        .parse_callbacks(Box::new(bindgen::CargoCallbacks))
-        // Finish the builder and generate the bindings.
-        .generate()
-        // Unwrap the Result and panic on failure.
-        .expect("Unable to generate bindings");
-
-    // Write the bindings to the $OUT_DIR/bindings.rs file.
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
-    bindings
-        .write_to_file(out_path.join("bindings.rs"))
-        .expect("Couldn't write bindings!");
-    Ok(())
+    Ok(Libsrt {
+        include_paths: vec![include_path],
+        // config_crypto() enables the AEAD API along with encryption.
+        aead_api: enclib.is_some(),
+    })
 }
@@ -0,0 +1,962 @@
+/* Transcribed by hand from srt.h of libsrt 1.5.2, with ENABLE_AEAD_API_PREVIEW,
+ * for Linux targets; it is not bindgen output and omits the group API. Replace
+ * it with $OUT_DIR/bindings.rs from a build with the `bindgen` feature. */
+
+pub const SRT_VERSION_MAJOR: u32 = 1;
+pub const SRT_VERSION_MINOR: u32 = 5;
+pub const SRT_VERSION_PATCH: u32 = 2;
+pub const SRT_VERSION_STRING: &'static [u8; 6usize] = b"1.5.2\0";
+pub const SRT_LIVE_DEF_PLSIZE: u32 = 1316;
+pub const SRT_LIVE_MAX_PLSIZE: u32 = 1456;
+pub const SRT_LIVE_DEF_LATENCY_MS: u32 = 120;
+pub const SRT_INVALID_SOCK: i32 = -1;
+pub const SRT_ERROR: i32 = -1;
+pub type sa_family_t = ::std::os::raw::c_ushort;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct sockaddr {
+    pub sa_family: sa_family_t,
+    pub sa_data: [::std::os::raw::c_char; 14usize],
+}
+pub type SRTSOCKET = i32;
+pub type SYSSOCKET = ::std::os::raw::c_int;
+pub type UDPSOCKET = SYSSOCKET;
+impl SRT_SOCKSTATUS {
+    pub const SRTS_INIT: SRT_SOCKSTATUS = SRT_SOCKSTATUS(1);
+}
+impl SRT_SOCKSTATUS {
+    pub const SRTS_OPENED: SRT_SOCKSTATUS = SRT_SOCKSTATUS(2);
+}
+impl SRT_SOCKSTATUS {
+    pub const SRTS_LISTENING: SRT_SOCKSTATUS = SRT_SOCKSTATUS(3);
+}
+impl SRT_SOCKSTATUS {
+    pub const SRTS_CONNECTING: SRT_SOCKSTATUS = SRT_SOCKSTATUS(4);
+}
+impl SRT_SOCKSTATUS {
+    pub const SRTS_CONNECTED: SRT_SOCKSTATUS = SRT_SOCKSTATUS(5);
+}
+impl SRT_SOCKSTATUS {
+    pub const SRTS_BROKEN: SRT_SOCKSTATUS = SRT_SOCKSTATUS(6);
+}
+impl SRT_SOCKSTATUS {
+    pub const SRTS_CLOSING: SRT_SOCKSTATUS = SRT_SOCKSTATUS(7);
+}
+impl SRT_SOCKSTATUS {
+    pub const SRTS_CLOSED: SRT_SOCKSTATUS = SRT_SOCKSTATUS(8);
+}
+impl SRT_SOCKSTATUS {
+    pub const SRTS_NONEXIST: SRT_SOCKSTATUS = SRT_SOCKSTATUS(9);
+}
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct SRT_SOCKSTATUS(pub ::std::os::raw::c_uint);
+impl SRT_SOCKOPT {
+    pub const SRTO_MSS: SRT_SOCKOPT = SRT_SOCKOPT(0);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_SNDSYN: SRT_SOCKOPT = SRT_SOCKOPT(1);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_RCVSYN: SRT_SOCKOPT = SRT_SOCKOPT(2);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_ISN: SRT_SOCKOPT = SRT_SOCKOPT(3);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_FC: SRT_SOCKOPT = SRT_SOCKOPT(4);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_SNDBUF: SRT_SOCKOPT = SRT_SOCKOPT(5);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_RCVBUF: SRT_SOCKOPT = SRT_SOCKOPT(6);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_LINGER: SRT_SOCKOPT = SRT_SOCKOPT(7);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_UDP_SNDBUF: SRT_SOCKOPT = SRT_SOCKOPT(8);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_UDP_RCVBUF: SRT_SOCKOPT = SRT_SOCKOPT(9);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_RENDEZVOUS: SRT_SOCKOPT = SRT_SOCKOPT(12);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_SNDTIMEO: SRT_SOCKOPT = SRT_SOCKOPT(13);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_RCVTIMEO: SRT_SOCKOPT = SRT_SOCKOPT(14);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_REUSEADDR: SRT_SOCKOPT = SRT_SOCKOPT(15);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_MAXBW: SRT_SOCKOPT = SRT_SOCKOPT(16);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_STATE: SRT_SOCKOPT = SRT_SOCKOPT(17);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_EVENT: SRT_SOCKOPT = SRT_SOCKOPT(18);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_SNDDATA: SRT_SOCKOPT = SRT_SOCKOPT(19);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_RCVDATA: SRT_SOCKOPT = SRT_SOCKOPT(20);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_SENDER: SRT_SOCKOPT = SRT_SOCKOPT(21);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_TSBPDMODE: SRT_SOCKOPT = SRT_SOCKOPT(22);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_LATENCY: SRT_SOCKOPT = SRT_SOCKOPT(23);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_INPUTBW: SRT_SOCKOPT = SRT_SOCKOPT(24);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_OHEADBW: SRT_SOCKOPT = SRT_SOCKOPT(25);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_PASSPHRASE: SRT_SOCKOPT = SRT_SOCKOPT(26);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_PBKEYLEN: SRT_SOCKOPT = SRT_SOCKOPT(27);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_KMSTATE: SRT_SOCKOPT = SRT_SOCKOPT(28);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_IPTTL: SRT_SOCKOPT = SRT_SOCKOPT(29);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_IPTOS: SRT_SOCKOPT = SRT_SOCKOPT(30);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_TLPKTDROP: SRT_SOCKOPT = SRT_SOCKOPT(31);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_SNDDROPDELAY: SRT_SOCKOPT = SRT_SOCKOPT(32);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_NAKREPORT: SRT_SOCKOPT = SRT_SOCKOPT(33);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_VERSION: SRT_SOCKOPT = SRT_SOCKOPT(34);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_PEERVERSION: SRT_SOCKOPT = SRT_SOCKOPT(35);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_CONNTIMEO: SRT_SOCKOPT = SRT_SOCKOPT(36);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_DRIFTTRACER: SRT_SOCKOPT = SRT_SOCKOPT(37);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_MININPUTBW: SRT_SOCKOPT = SRT_SOCKOPT(38);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_SNDKMSTATE: SRT_SOCKOPT = SRT_SOCKOPT(40);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_RCVKMSTATE: SRT_SOCKOPT = SRT_SOCKOPT(41);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_LOSSMAXTTL: SRT_SOCKOPT = SRT_SOCKOPT(42);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_RCVLATENCY: SRT_SOCKOPT = SRT_SOCKOPT(43);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_PEERLATENCY: SRT_SOCKOPT = SRT_SOCKOPT(44);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_MINVERSION: SRT_SOCKOPT = SRT_SOCKOPT(45);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_STREAMID: SRT_SOCKOPT = SRT_SOCKOPT(46);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_CONGESTION: SRT_SOCKOPT = SRT_SOCKOPT(47);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_MESSAGEAPI: SRT_SOCKOPT = SRT_SOCKOPT(48);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_PAYLOADSIZE: SRT_SOCKOPT = SRT_SOCKOPT(49);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_TRANSTYPE: SRT_SOCKOPT = SRT_SOCKOPT(50);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_KMREFRESHRATE: SRT_SOCKOPT = SRT_SOCKOPT(51);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_KMPREANNOUNCE: SRT_SOCKOPT = SRT_SOCKOPT(52);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_ENFORCEDENCRYPTION: SRT_SOCKOPT = SRT_SOCKOPT(53);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_IPV6ONLY: SRT_SOCKOPT = SRT_SOCKOPT(54);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_PEERIDLETIMEO: SRT_SOCKOPT = SRT_SOCKOPT(55);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_BINDTODEVICE: SRT_SOCKOPT = SRT_SOCKOPT(56);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_GROUPCONNECT: SRT_SOCKOPT = SRT_SOCKOPT(57);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_GROUPMINSTABLETIMEO: SRT_SOCKOPT = SRT_SOCKOPT(58);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_GROUPTYPE: SRT_SOCKOPT = SRT_SOCKOPT(59);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_PACKETFILTER: SRT_SOCKOPT = SRT_SOCKOPT(60);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_RETRANSMITALGO: SRT_SOCKOPT = SRT_SOCKOPT(61);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_CRYPTOMODE: SRT_SOCKOPT = SRT_SOCKOPT(62);
+}
+impl SRT_SOCKOPT {
+    pub const SRTO_E_SIZE: SRT_SOCKOPT = SRT_SOCKOPT(63);
+}
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct SRT_SOCKOPT(pub ::std::os::raw::c_uint);
+impl SRT_TRANSTYPE {
+    pub const SRTT_LIVE: SRT_TRANSTYPE = SRT_TRANSTYPE(0);
+}
+impl SRT_TRANSTYPE {
+    pub const SRTT_FILE: SRT_TRANSTYPE = SRT_TRANSTYPE(1);
+}
+impl SRT_TRANSTYPE {
+    pub const SRTT_INVALID: SRT_TRANSTYPE = SRT_TRANSTYPE(2);
+}
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct SRT_TRANSTYPE(pub ::std::os::raw::c_uint);
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CBytePerfMon {
+    pub msTimeStamp: i64,
+    pub pktSentTotal: i64,
+    pub pktRecvTotal: i64,
+    pub pktSndLossTotal: ::std::os::raw::c_int,
+    pub pktRcvLossTotal: ::std::os::raw::c_int,
+    pub pktRetransTotal: ::std::os::raw::c_int,
+    pub pktSentACKTotal: ::std::os::raw::c_int,
+    pub pktRecvACKTotal: ::std::os::raw::c_int,
+    pub pktSentNAKTotal: ::std::os::raw::c_int,
+    pub pktRecvNAKTotal: ::std::os::raw::c_int,
+    pub usSndDurationTotal: i64,
+    pub pktSndDropTotal: ::std::os::raw::c_int,
+    pub pktRcvDropTotal: ::std::os::raw::c_int,
+    pub pktRcvUndecryptTotal: ::std::os::raw::c_int,
+    pub byteSentTotal: u64,
+    pub byteRecvTotal: u64,
+    pub byteRcvLossTotal: u64,
+    pub byteRetransTotal: u64,
+    pub byteSndDropTotal: u64,
+    pub byteRcvDropTotal: u64,
+    pub byteRcvUndecryptTotal: u64,
+    pub pktSent: i64,
+    pub pktRecv: i64,
+    pub pktSndLoss: ::std::os::raw::c_int,
+    pub pktRcvLoss: ::std::os::raw::c_int,
+    pub pktRetrans: ::std::os::raw::c_int,
+    pub pktRcvRetrans: ::std::os::raw::c_int,
+    pub pktSentACK: ::std::os::raw::c_int,
+    pub pktRecvACK: ::std::os::raw::c_int,
+    pub pktSentNAK: ::std::os::raw::c_int,
+    pub pktRecvNAK: ::std::os::raw::c_int,
+    pub mbpsSendRate: f64,
+    pub mbpsRecvRate: f64,
+    pub usSndDuration: i64,
+    pub pktReorderDistance: ::std::os::raw::c_int,
+    pub pktRcvAvgBelatedTime: f64,
+    pub pktRcvBelated: i64,
+    pub pktSndDrop: ::std::os::raw::c_int,
+    pub pktRcvDrop: ::std::os::raw::c_int,
+    pub pktRcvUndecrypt: ::std::os::raw::c_int,
+    pub byteSent: u64,
+    pub byteRecv: u64,
+    pub byteRcvLoss: u64,
+    pub byteRetrans: u64,
+    pub byteSndDrop: u64,
+    pub byteRcvDrop: u64,
+    pub byteRcvUndecrypt: u64,
+    pub usPktSndPeriod: f64,
+    pub pktFlowWindow: ::std::os::raw::c_int,
+    pub pktCongestionWindow: ::std::os::raw::c_int,
+    pub pktFlightSize: ::std::os::raw::c_int,
+    pub msRTT: f64,
+    pub mbpsBandwidth: f64,
+    pub byteAvailSndBuf: ::std::os::raw::c_int,
+    pub byteAvailRcvBuf: ::std::os::raw::c_int,
+    pub mbpsMaxBW: f64,
+    pub byteMSS: ::std::os::raw::c_int,
+    pub pktSndBuf: ::std::os::raw::c_int,
+    pub byteSndBuf: ::std::os::raw::c_int,
+    pub msSndBuf: ::std::os::raw::c_int,
+    pub msSndTsbPdDelay: ::std::os::raw::c_int,
+    pub pktRcvBuf: ::std::os::raw::c_int,
+    pub byteRcvBuf: ::std::os::raw::c_int,
+    pub msRcvBuf: ::std::os::raw::c_int,
+    pub msRcvTsbPdDelay: ::std::os::raw::c_int,
+    pub pktSndFilterExtraTotal: ::std::os::raw::c_int,
+    pub pktRcvFilterExtraTotal: ::std::os::raw::c_int,
+    pub pktRcvFilterSupplyTotal: ::std::os::raw::c_int,
+    pub pktRcvFilterLossTotal: ::std::os::raw::c_int,
+    pub pktSndFilterExtra: ::std::os::raw::c_int,
+    pub pktRcvFilterExtra: ::std::os::raw::c_int,
+    pub pktRcvFilterSupply: ::std::os::raw::c_int,
+    pub pktRcvFilterLoss: ::std::os::raw::c_int,
+    pub pktReorderTolerance: ::std::os::raw::c_int,
+    pub pktSentUniqueTotal: i64,
+    pub pktRecvUniqueTotal: i64,
+    pub byteSentUniqueTotal: u64,
+    pub byteRecvUniqueTotal: u64,
+    pub pktSentUnique: i64,
+    pub pktRecvUnique: i64,
+    pub byteSentUnique: u64,
+    pub byteRecvUnique: u64,
+}
+pub type SRT_TRACEBSTATS = CBytePerfMon;
+impl SRT_ERRNO {
+    pub const SRT_EUNKNOWN: SRT_ERRNO = SRT_ERRNO(-1);
+}
+impl SRT_ERRNO {
+    pub const SRT_SUCCESS: SRT_ERRNO = SRT_ERRNO(0);
+}
+impl SRT_ERRNO {
+    pub const SRT_ECONNSETUP: SRT_ERRNO = SRT_ERRNO(1000);
+}
+impl SRT_ERRNO {
+    pub const SRT_ENOSERVER: SRT_ERRNO = SRT_ERRNO(1001);
+}
+impl SRT_ERRNO {
+    pub const SRT_ECONNREJ: SRT_ERRNO = SRT_ERRNO(1002);
+}
+impl SRT_ERRNO {
+    pub const SRT_ESOCKFAIL: SRT_ERRNO = SRT_ERRNO(1003);
+}
+impl SRT_ERRNO {
+    pub const SRT_ESECFAIL: SRT_ERRNO = SRT_ERRNO(1004);
+}
+impl SRT_ERRNO {
+    pub const SRT_ESCLOSED: SRT_ERRNO = SRT_ERRNO(1005);
+}
+impl SRT_ERRNO {
+    pub const SRT_ECONNFAIL: SRT_ERRNO = SRT_ERRNO(2000);
+}
+impl SRT_ERRNO {
+    pub const SRT_ECONNLOST: SRT_ERRNO = SRT_ERRNO(2001);
+}
+impl SRT_ERRNO {
+    pub const SRT_ENOCONN: SRT_ERRNO = SRT_ERRNO(2002);
+}
+impl SRT_ERRNO {
+    pub const SRT_ERESOURCE: SRT_ERRNO = SRT_ERRNO(3000);
+}
+impl SRT_ERRNO {
+    pub const SRT_ETHREAD: SRT_ERRNO = SRT_ERRNO(3001);
+}
+impl SRT_ERRNO {
+    pub const SRT_ENOBUF: SRT_ERRNO = SRT_ERRNO(3002);
+}
+impl SRT_ERRNO {
+    pub const SRT_ESYSOBJ: SRT_ERRNO = SRT_ERRNO(3003);
+}
+impl SRT_ERRNO {
+    pub const SRT_EFILE: SRT_ERRNO = SRT_ERRNO(4000);
+}
+impl SRT_ERRNO {
+    pub const SRT_EINVRDOFF: SRT_ERRNO = SRT_ERRNO(4001);
+}
+impl SRT_ERRNO {
+    pub const SRT_ERDPERM: SRT_ERRNO = SRT_ERRNO(4002);
+}
+impl SRT_ERRNO {
+    pub const SRT_EINVWROFF: SRT_ERRNO = SRT_ERRNO(4003);
+}
+impl SRT_ERRNO {
+    pub const SRT_EWRPERM: SRT_ERRNO = SRT_ERRNO(4004);
+}
+impl SRT_ERRNO {
+    pub const SRT_EINVOP: SRT_ERRNO = SRT_ERRNO(5000);
+}
+impl SRT_ERRNO {
+    pub const SRT_EBOUNDSOCK: SRT_ERRNO = SRT_ERRNO(5001);
+}
+impl SRT_ERRNO {
+    pub const SRT_ECONNSOCK: SRT_ERRNO = SRT_ERRNO(5002);
+}
+impl SRT_ERRNO {
+    pub const SRT_EINVPARAM: SRT_ERRNO = SRT_ERRNO(5003);
+}
+impl SRT_ERRNO {
+    pub const SRT_EINVSOCK: SRT_ERRNO = SRT_ERRNO(5004);
+}
+impl SRT_ERRNO {
+    pub const SRT_EUNBOUNDSOCK: SRT_ERRNO = SRT_ERRNO(5005);
+}
+impl SRT_ERRNO {
+    pub const SRT_ENOLISTEN: SRT_ERRNO = SRT_ERRNO(5006);
+}
+impl SRT_ERRNO {
+    pub const SRT_ERDVNOSERV: SRT_ERRNO = SRT_ERRNO(5007);
+}
+impl SRT_ERRNO {
+    pub const SRT_ERDVUNBOUND: SRT_ERRNO = SRT_ERRNO(5008);
+}
+impl SRT_ERRNO {
+    pub const SRT_EINVALMSGAPI: SRT_ERRNO = SRT_ERRNO(5009);
+}
+impl SRT_ERRNO {
+    pub const SRT_EINVALBUFFERAPI: SRT_ERRNO = SRT_ERRNO(5010);
+}
+impl SRT_ERRNO {
+    pub const SRT_EDUPLISTEN: SRT_ERRNO = SRT_ERRNO(5011);
+}
+impl SRT_ERRNO {
+    pub const SRT_ELARGEMSG: SRT_ERRNO = SRT_ERRNO(5012);
+}
+impl SRT_ERRNO {
+    pub const SRT_EINVPOLLID: SRT_ERRNO = SRT_ERRNO(5013);
+}
+impl SRT_ERRNO {
+    pub const SRT_EPOLLEMPTY: SRT_ERRNO = SRT_ERRNO(5014);
+}
+impl SRT_ERRNO {
+    pub const SRT_EBINDCONFLICT: SRT_ERRNO = SRT_ERRNO(5015);
+}
+impl SRT_ERRNO {
+    pub const SRT_EASYNCFAIL: SRT_ERRNO = SRT_ERRNO(6000);
+}
+impl SRT_ERRNO {
+    pub const SRT_EASYNCSND: SRT_ERRNO = SRT_ERRNO(6001);
+}
+impl SRT_ERRNO {
+    pub const SRT_EASYNCRCV: SRT_ERRNO = SRT_ERRNO(6002);
+}
+impl SRT_ERRNO {
+    pub const SRT_ETIMEOUT: SRT_ERRNO = SRT_ERRNO(6003);
+}
+impl SRT_ERRNO {
+    pub const SRT_ECONGEST: SRT_ERRNO = SRT_ERRNO(6004);
+}
+impl SRT_ERRNO {
+    pub const SRT_EPEERERR: SRT_ERRNO = SRT_ERRNO(7000);
+}
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct SRT_ERRNO(pub ::std::os::raw::c_int);
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_UNKNOWN: SRT_REJECT_REASON = SRT_REJECT_REASON(0);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_SYSTEM: SRT_REJECT_REASON = SRT_REJECT_REASON(1);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_PEER: SRT_REJECT_REASON = SRT_REJECT_REASON(2);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_RESOURCE: SRT_REJECT_REASON = SRT_REJECT_REASON(3);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_ROGUE: SRT_REJECT_REASON = SRT_REJECT_REASON(4);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_BACKLOG: SRT_REJECT_REASON = SRT_REJECT_REASON(5);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_IPE: SRT_REJECT_REASON = SRT_REJECT_REASON(6);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_CLOSE: SRT_REJECT_REASON = SRT_REJECT_REASON(7);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_VERSION: SRT_REJECT_REASON = SRT_REJECT_REASON(8);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_RDVCOOKIE: SRT_REJECT_REASON = SRT_REJECT_REASON(9);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_BADSECRET: SRT_REJECT_REASON = SRT_REJECT_REASON(10);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_UNSECURE: SRT_REJECT_REASON = SRT_REJECT_REASON(11);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_MESSAGEAPI: SRT_REJECT_REASON = SRT_REJECT_REASON(12);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_CONGESTION: SRT_REJECT_REASON = SRT_REJECT_REASON(13);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_FILTER: SRT_REJECT_REASON = SRT_REJECT_REASON(14);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_GROUP: SRT_REJECT_REASON = SRT_REJECT_REASON(15);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_TIMEOUT: SRT_REJECT_REASON = SRT_REJECT_REASON(16);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_CRYPTO: SRT_REJECT_REASON = SRT_REJECT_REASON(17);
+}
+impl SRT_REJECT_REASON {
+    pub const SRT_REJ_E_SIZE: SRT_REJECT_REASON = SRT_REJECT_REASON(18);
+}
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct SRT_REJECT_REASON(pub ::std::os::raw::c_uint);
+impl SRT_KM_STATE {
+    pub const SRT_KM_S_UNSECURED: SRT_KM_STATE = SRT_KM_STATE(0);
+}
+impl SRT_KM_STATE {
+    pub const SRT_KM_S_SECURING: SRT_KM_STATE = SRT_KM_STATE(1);
+}
+impl SRT_KM_STATE {
+    pub const SRT_KM_S_SECURED: SRT_KM_STATE = SRT_KM_STATE(2);
+}
+impl SRT_KM_STATE {
+    pub const SRT_KM_S_NOSECRET: SRT_KM_STATE = SRT_KM_STATE(3);
+}
+impl SRT_KM_STATE {
+    pub const SRT_KM_S_BADSECRET: SRT_KM_STATE = SRT_KM_STATE(4);
+}
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct SRT_KM_STATE(pub ::std::os::raw::c_uint);
+impl SRT_EPOLL_OPT {
+    pub const SRT_EPOLL_OPT_NONE: SRT_EPOLL_OPT = SRT_EPOLL_OPT(0);
+}
+impl SRT_EPOLL_OPT {
+    pub const SRT_EPOLL_IN: SRT_EPOLL_OPT = SRT_EPOLL_OPT(1);
+}
+impl SRT_EPOLL_OPT {
+    pub const SRT_EPOLL_OUT: SRT_EPOLL_OPT = SRT_EPOLL_OPT(4);
+}
+impl SRT_EPOLL_OPT {
+    pub const SRT_EPOLL_ERR: SRT_EPOLL_OPT = SRT_EPOLL_OPT(8);
+}
+impl SRT_EPOLL_OPT {
+    pub const SRT_EPOLL_CONNECT: SRT_EPOLL_OPT = SRT_EPOLL_OPT(4);
+}
+impl SRT_EPOLL_OPT {
+    pub const SRT_EPOLL_ACCEPT: SRT_EPOLL_OPT = SRT_EPOLL_OPT(1);
+}
+impl SRT_EPOLL_OPT {
+    pub const SRT_EPOLL_UPDATE: SRT_EPOLL_OPT = SRT_EPOLL_OPT(16);
+}
+impl SRT_EPOLL_OPT {
+    pub const SRT_EPOLL_ET: SRT_EPOLL_OPT = SRT_EPOLL_OPT(2147483648);
+}
+impl ::std::ops::BitOr<SRT_EPOLL_OPT> for SRT_EPOLL_OPT {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        SRT_EPOLL_OPT(self.0 | other.0)
+    }
+}
+impl ::std::ops::BitOrAssign for SRT_EPOLL_OPT {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: SRT_EPOLL_OPT) {
+        self.0 |= rhs.0;
+    }
+}
+impl ::std::ops::BitAnd<SRT_EPOLL_OPT> for SRT_EPOLL_OPT {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, other: Self) -> Self {
+        SRT_EPOLL_OPT(self.0 & other.0)
+    }
+}
+impl ::std::ops::BitAndAssign for SRT_EPOLL_OPT {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: SRT_EPOLL_OPT) {
+        self.0 &= rhs.0;
+    }
+}
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct SRT_EPOLL_OPT(pub ::std::os::raw::c_uint);
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct SRT_EPOLL_EVENT_STR {
+    pub fd: SRTSOCKET,
+    pub events: ::std::os::raw::c_int,
+}
+pub type SRT_EPOLL_EVENT = SRT_EPOLL_EVENT_STR;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct SRT_SocketGroupData_ {
+    _unused: [u8; 0],
+}
+pub type SRT_SOCKGROUPDATA = SRT_SocketGroupData_;
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct SRT_MsgCtrl_ {
+    pub flags: ::std::os::raw::c_int,
+    pub msgttl: ::std::os::raw::c_int,
+    pub inorder: ::std::os::raw::c_int,
+    pub boundary: ::std::os::raw::c_int,
+    pub srctime: i64,
+    pub pktseq: i32,
+    pub msgno: i32,
+    pub grpdata: *mut SRT_SOCKGROUPDATA,
+    pub grpdata_size: usize,
+}
+pub type SRT_MSGCTRL = SRT_MsgCtrl_;
+pub type srt_listen_callback_fn = ::std::option::Option<
+    unsafe extern "C" fn(
+        opaq: *mut ::std::os::raw::c_void,
+        ns: SRTSOCKET,
+        hsversion: ::std::os::raw::c_int,
+        peeraddr: *const sockaddr,
+        streamid: *const ::std::os::raw::c_char,
+    ) -> ::std::os::raw::c_int,
+>;
+pub type srt_connect_callback_fn = ::std::option::Option<
+    unsafe extern "C" fn(
+        opaq: *mut ::std::os::raw::c_void,
+        ns: SRTSOCKET,
+        errorcode: ::std::os::raw::c_int,
+        peeraddr: *const sockaddr,
+        token: ::std::os::raw::c_int,
+    ),
+>;
+extern "C" {
+    pub static srt_msgctrl_default: SRT_MSGCTRL;
+}
+extern "C" {
+    pub fn srt_msgctrl_init(mctrl: *mut SRT_MSGCTRL);
+}
+extern "C" {
+    pub fn srt_startup() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_cleanup() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_create_socket() -> SRTSOCKET;
+}
+extern "C" {
+    pub fn srt_bind(
+        u: SRTSOCKET,
+        name: *const sockaddr,
+        namelen: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_bind_acquire(u: SRTSOCKET, sys_udp_sock: UDPSOCKET) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_listen(u: SRTSOCKET, backlog: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_accept(
+        u: SRTSOCKET,
+        addr: *mut sockaddr,
+        addrlen: *mut ::std::os::raw::c_int,
+    ) -> SRTSOCKET;
+}
+extern "C" {
+    pub fn srt_listen_callback(
+        lsn: SRTSOCKET,
+        hook_fn: srt_listen_callback_fn,
+        hook_opaque: *mut ::std::os::raw::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_connect_callback(
+        clr: SRTSOCKET,
+        hook_fn: srt_connect_callback_fn,
+        hook_opaque: *mut ::std::os::raw::c_void,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_connect(
+        u: SRTSOCKET,
+        name: *const sockaddr,
+        namelen: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_connect_bind(
+        u: SRTSOCKET,
+        source: *const sockaddr,
+        target: *const sockaddr,
+        len: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_rendezvous(
+        u: SRTSOCKET,
+        local_name: *const sockaddr,
+        local_namelen: ::std::os::raw::c_int,
+        remote_name: *const sockaddr,
+        remote_namelen: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_close(u: SRTSOCKET) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_getpeername(
+        u: SRTSOCKET,
+        name: *mut sockaddr,
+        namelen: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_getsockname(
+        u: SRTSOCKET,
+        name: *mut sockaddr,
+        namelen: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_getsockopt(
+        u: SRTSOCKET,
+        level: ::std::os::raw::c_int,
+        optname: SRT_SOCKOPT,
+        optval: *mut ::std::os::raw::c_void,
+        optlen: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_setsockopt(
+        u: SRTSOCKET,
+        level: ::std::os::raw::c_int,
+        optname: SRT_SOCKOPT,
+        optval: *const ::std::os::raw::c_void,
+        optlen: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_getsockflag(
+        u: SRTSOCKET,
+        opt: SRT_SOCKOPT,
+        optval: *mut ::std::os::raw::c_void,
+        optlen: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_setsockflag(
+        u: SRTSOCKET,
+        opt: SRT_SOCKOPT,
+        optval: *const ::std::os::raw::c_void,
+        optlen: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_send(
+        u: SRTSOCKET,
+        buf: *const ::std::os::raw::c_char,
+        len: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_sendmsg(
+        u: SRTSOCKET,
+        buf: *const ::std::os::raw::c_char,
+        len: ::std::os::raw::c_int,
+        ttl: ::std::os::raw::c_int,
+        inorder: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_sendmsg2(
+        u: SRTSOCKET,
+        buf: *const ::std::os::raw::c_char,
+        len: ::std::os::raw::c_int,
+        mctrl: *mut SRT_MSGCTRL,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_recv(
+        u: SRTSOCKET,
+        buf: *mut ::std::os::raw::c_char,
+        len: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_recvmsg(
+        u: SRTSOCKET,
+        buf: *mut ::std::os::raw::c_char,
+        len: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_recvmsg2(
+        u: SRTSOCKET,
+        buf: *mut ::std::os::raw::c_char,
+        len: ::std::os::raw::c_int,
+        mctrl: *mut SRT_MSGCTRL,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_getlasterror_str() -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn srt_getlasterror(errno_loc: *mut ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_strerror(
+        code: ::std::os::raw::c_int,
+        errnoval: ::std::os::raw::c_int,
+    ) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn srt_clearlasterror();
+}
+extern "C" {
+    pub fn srt_bstats(
+        u: SRTSOCKET,
+        perf: *mut SRT_TRACEBSTATS,
+        clear: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_bistats(
+        u: SRTSOCKET,
+        perf: *mut SRT_TRACEBSTATS,
+        clear: ::std::os::raw::c_int,
+        instantaneous: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_getsockstate(u: SRTSOCKET) -> SRT_SOCKSTATUS;
+}
+extern "C" {
+    pub fn srt_epoll_create() -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_epoll_clear_usocks(eid: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_epoll_add_usock(
+        eid: ::std::os::raw::c_int,
+        u: SRTSOCKET,
+        events: *const ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_epoll_remove_usock(eid: ::std::os::raw::c_int, u: SRTSOCKET)
+        -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_epoll_update_usock(
+        eid: ::std::os::raw::c_int,
+        u: SRTSOCKET,
+        events: *const ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_epoll_uwait(
+        eid: ::std::os::raw::c_int,
+        fdsSet: *mut SRT_EPOLL_EVENT,
+        fdsSize: ::std::os::raw::c_int,
+        msTimeOut: i64,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_epoll_add_ssock(
+        eid: ::std::os::raw::c_int,
+        s: SYSSOCKET,
+        events: *const ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_epoll_remove_ssock(eid: ::std::os::raw::c_int, s: SYSSOCKET)
+        -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_epoll_update_ssock(
+        eid: ::std::os::raw::c_int,
+        s: SYSSOCKET,
+        events: *const ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_epoll_wait(
+        eid: ::std::os::raw::c_int,
+        readfds: *mut SRTSOCKET,
+        rnum: *mut ::std::os::raw::c_int,
+        writefds: *mut SRTSOCKET,
+        wnum: *mut ::std::os::raw::c_int,
+        msTimeOut: i64,
+        lrfds: *mut SYSSOCKET,
+        lrnum: *mut ::std::os::raw::c_int,
+        lwfds: *mut SYSSOCKET,
+        lwnum: *mut ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_epoll_set(eid: ::std::os::raw::c_int, flags: i32) -> i32;
+}
+extern "C" {
+    pub fn srt_epoll_release(eid: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_setloglevel(ll: ::std::os::raw::c_int);
+}
+extern "C" {
+    pub fn srt_addlogfa(fa: ::std::os::raw::c_int);
+}
+extern "C" {
+    pub fn srt_dellogfa(fa: ::std::os::raw::c_int);
+}
+extern "C" {
+    pub fn srt_resetlogfa(fara: *const ::std::os::raw::c_int, fara_size: usize);
+}
+extern "C" {
+    pub fn srt_setlogflags(flags: ::std::os::raw::c_int);
+}
+extern "C" {
+    pub fn srt_getsndbuffer(
+        sock: SRTSOCKET,
+        blocks: *mut usize,
+        bytes: *mut usize,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_getrejectreason(sock: SRTSOCKET) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_setrejectreason(
+        sock: SRTSOCKET,
+        value: ::std::os::raw::c_int,
+    ) -> ::std::os::raw::c_int;
+}
+extern "C" {
+    pub fn srt_rejectreason_str(id: ::std::os::raw::c_int) -> *const ::std::os::raw::c_char;
+}
+extern "C" {
+    pub fn srt_getversion() -> u32;
+}
+extern "C" {
+    pub fn srt_time_now() -> i64;
+}
+extern "C" {
+    pub fn srt_connection_time(sock: SRTSOCKET) -> i64;
+}
+extern "C" {
+    pub fn srt_clock_type() -> ::std::os::raw::c_int;
+}
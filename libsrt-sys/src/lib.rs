@@ -3,6 +3,33 @@
 #![allow(non_snake_case)]
 
 extern crate link_cplusplus;
+#[cfg(any(feature = "crypto-openssl", feature = "crypto-openssl-evp"))]
 extern crate openssl_sys;
 
+#[cfg(feature = "bindgen")]
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+#[cfg(not(feature = "bindgen"))]
+include!("bindings.rs");
+
+/// Whether libsrt was built with ENABLE_AEAD_API_PREVIEW. Without it,
+/// `SRT_SOCKOPT::SRTO_CRYPTOMODE` is not an option libsrt knows.
+pub const AEAD_API: bool = cfg!(srt_aead_api);
+
+// The checked-in bindings use Linux layouts, e.g. for `sockaddr` and
+// `SYSSOCKET`.
+#[cfg(not(any(feature = "bindgen", target_os = "linux")))]
+compile_error!("the checked-in libsrt bindings only cover Linux; enable the `bindgen` feature");
+
+#[cfg(all(test, feature = "bindgen"))]
+mod tests {
+    #[test]
+    fn test_checked_in_bindings_up_to_date() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+        let checked_in = include_str!("bindings.rs");
+        assert!(
+            generated == checked_in,
+            "src/bindings.rs differs from the bindings generated from wrapper.h; \
+             copy $OUT_DIR/bindings.rs over it"
+        );
+    }
+}
@@ -1 +1,2 @@
+#define ENABLE_AEAD_API_PREVIEW
 #include <srt.h>
\ No newline at end of file
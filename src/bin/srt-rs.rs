@@ -0,0 +1,384 @@
+//! Moves live data between SRT, UDP, files and stdin/stdout, in the spirit of
+//! libsrt's srt-live-transmit.
+//!
+//! ```text
+//! srt-rs [-a yes|no] [-s <ms>] [-c <bytes>] [-b <bits/s>] <input-uri> <output-uri>
+//!
+//! srt://[host]:port[?mode=caller|listener&latency=<ms>&passphrase=<p>&streamid=<id>]
+//! udp://[host]:port[?adapter=<ip>&ttl=<hops>]
+//! file://<path>, file://con or - for stdin/stdout
+//! ```
+//!
+//! An `srt://` URI without a host listens, otherwise it calls. A multicast
+//! `udp://` group is joined for input and sent to for output.
+
+#[path = "srt-rs/uri.rs"]
+mod uri;
+
+use srt_rs as srt;
+
+use std::{
+    env,
+    fs::File,
+    io::{self, ErrorKind, Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket},
+    process, thread,
+    time::{Duration, Instant},
+};
+
+use uri::Uri;
+
+const USAGE: &str =
+    "usage: srt-rs [-a yes|no] [-s <ms>] [-c <bytes>] [-b <bits/s>] <input-uri> <output-uri>";
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+// Reads give up after this long so that stats are printed on idle links too.
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+const LIVE_PAYLOAD_SIZE: usize = 1316;
+
+struct Options {
+    auto_reconnect: bool,
+    stats_interval: Option<Duration>,
+    chunk_size: usize,
+    bitrate: Option<u64>,
+    input: Uri,
+    output: Uri,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut auto_reconnect = true;
+    let mut stats_interval = None;
+    let mut chunk_size = LIVE_PAYLOAD_SIZE;
+    let mut bitrate = None;
+    let mut uris = Vec::new();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("{} needs a value", name));
+        match arg.as_str() {
+            "-a" => {
+                auto_reconnect = match value("-a")?.as_str() {
+                    "yes" => true,
+                    "no" => false,
+                    other => return Err(format!("-a takes yes or no, got {}", other)),
+                }
+            }
+            "-s" => stats_interval = Some(Duration::from_millis(parse_number(&value("-s")?)?)),
+            "-c" => chunk_size = parse_number(&value("-c")?)? as usize,
+            "-b" => bitrate = Some(parse_number(&value("-b")?)?),
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ => uris.push(Uri::parse(&arg)?),
+        }
+    }
+    if chunk_size == 0 {
+        return Err("-c must be at least 1".to_string());
+    }
+    if bitrate == Some(0) {
+        return Err("-b must be at least 1".to_string());
+    }
+    let mut uris = uris.into_iter();
+    let (input, output) = match (uris.next(), uris.next(), uris.next()) {
+        (Some(input), Some(output), None) => (input, output),
+        _ => return Err(USAGE.to_string()),
+    };
+    // Live mode cannot send a larger message, and retrying would not help.
+    if output.scheme == "srt" && chunk_size > LIVE_PAYLOAD_SIZE {
+        return Err(format!(
+            "-c can be at most {} with an srt:// output",
+            LIVE_PAYLOAD_SIZE
+        ));
+    }
+    Ok(Options {
+        auto_reconnect,
+        stats_interval,
+        chunk_size,
+        bitrate,
+        input,
+        output,
+    })
+}
+
+fn parse_number(value: &str) -> Result<u64, String> {
+    value
+        .parse()
+        .map_err(|_| format!("expected a number, got {}", value))
+}
+
+enum Conn {
+    Srt(srt::SrtStream),
+    Udp(UdpSocket, Option<SocketAddr>),
+    Stdin(io::Stdin),
+    Stdout(io::Stdout),
+    File(File),
+}
+
+struct Endpoint {
+    uri: Uri,
+    input: bool,
+    listener: Option<srt::SrtListener>,
+    conn: Option<Conn>,
+}
+
+impl Endpoint {
+    fn new(uri: Uri, input: bool) -> Self {
+        Self {
+            uri,
+            input,
+            listener: None,
+            conn: None,
+        }
+    }
+
+    fn open(&mut self) -> Result<(), String> {
+        let conn = match self.uri.scheme.as_str() {
+            "srt" => Conn::Srt(self.open_srt()?),
+            "udp" => self.open_udp()?,
+            _ => match (self.uri.path.as_str(), self.input) {
+                ("con", true) => Conn::Stdin(io::stdin()),
+                ("con", false) => Conn::Stdout(io::stdout()),
+                (path, true) => Conn::File(File::open(path).map_err(|e| e.to_string())?),
+                (path, false) => Conn::File(File::create(path).map_err(|e| e.to_string())?),
+            },
+        };
+        self.conn = Some(conn);
+        Ok(())
+    }
+
+    fn open_srt(&mut self) -> Result<srt::SrtStream, String> {
+        let uri = &self.uri;
+        let mut builder = srt::builder().set_live_transmission_type();
+        if let Some(latency) = uri.option("latency")? {
+            builder = builder
+                .set_receive_latency(latency)
+                .set_peer_latency(latency);
+        }
+        if let Some(passphrase) = uri.query.get("passphrase") {
+            let passphrase =
+                srt::Passphrase::new(passphrase.as_str()).map_err(|e| e.to_string())?;
+            builder = builder.set_passphrase(passphrase);
+        }
+        if let Some(stream_id) = uri.query.get("streamid") {
+            builder = builder.set_stream_id(stream_id.clone());
+        }
+        let stream = if uri.is_listener() {
+            if self.listener.is_none() {
+                let addr = SocketAddr::new(uri.ip()?, uri.port);
+                self.listener = Some(builder.listen(addr, 1).map_err(|e| e.to_string())?);
+                eprintln!("{}: listening", uri);
+            }
+            let listener = self.listener.as_ref().unwrap();
+            let (stream, peer) = listener.accept().map_err(|e| e.to_string())?;
+            eprintln!("{}: accepted {}", uri, peer);
+            stream
+        } else {
+            let stream = builder
+                .connect((uri.host.as_str(), uri.port))
+                .map_err(|e| e.to_string())?;
+            eprintln!("{}: connected", uri);
+            stream
+        };
+        if self.input {
+            stream
+                .set_receive_timeout(READ_TIMEOUT.as_millis() as i32)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(stream)
+    }
+
+    fn open_udp(&self) -> Result<Conn, String> {
+        let uri = &self.uri;
+        let ip = uri.ip()?;
+        let adapter = uri.option::<IpAddr>("adapter")?;
+        let unspecified = match ip {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        };
+        let socket = if self.input {
+            let bind_ip = if ip.is_multicast() { unspecified } else { ip };
+            let socket = UdpSocket::bind((bind_ip, uri.port)).map_err(|e| e.to_string())?;
+            match (ip, adapter) {
+                (IpAddr::V4(group), Some(IpAddr::V4(adapter))) if group.is_multicast() => {
+                    socket.join_multicast_v4(&group, &adapter)
+                }
+                (IpAddr::V4(group), _) if group.is_multicast() => {
+                    socket.join_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)
+                }
+                (IpAddr::V6(group), _) if group.is_multicast() => {
+                    socket.join_multicast_v6(&group, 0)
+                }
+                _ => Ok(()),
+            }
+            .map_err(|e| e.to_string())?;
+            socket
+                .set_read_timeout(Some(READ_TIMEOUT))
+                .map_err(|e| e.to_string())?;
+            socket
+        } else {
+            let socket =
+                UdpSocket::bind((adapter.unwrap_or(unspecified), 0)).map_err(|e| e.to_string())?;
+            if let Some(ttl) = uri.option("ttl")? {
+                if ip.is_multicast() {
+                    socket.set_multicast_ttl_v4(ttl)
+                } else {
+                    socket.set_ttl(ttl)
+                }
+                .map_err(|e| e.to_string())?;
+            }
+            socket
+        };
+        let destination = if self.input {
+            None
+        } else {
+            Some(SocketAddr::new(ip, uri.port))
+        };
+        Ok(Conn::Udp(socket, destination))
+    }
+
+    fn reconnect(&mut self) {
+        self.conn = None;
+        loop {
+            thread::sleep(RECONNECT_DELAY);
+            match self.open() {
+                Ok(()) => return,
+                Err(e) => eprintln!("{}: {}", self.uri, e),
+            }
+        }
+    }
+
+    fn can_reconnect(&self) -> bool {
+        self.uri.scheme == "srt"
+    }
+
+    /// `Ok(0)` is the end of the input; timeouts surface as `WouldBlock` or
+    /// `TimedOut` errors.
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.conn.as_mut() {
+            Some(Conn::Srt(stream)) => stream.read(buf),
+            Some(Conn::Udp(socket, _)) => socket.recv(buf),
+            Some(Conn::Stdin(stdin)) => stdin.read(buf),
+            Some(Conn::File(file)) => file.read(buf),
+            _ => Err(io::Error::other("not readable")),
+        }
+    }
+
+    fn write(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self.conn.as_mut() {
+            Some(Conn::Srt(stream)) => stream.write(buf).map(|_| ()),
+            Some(Conn::Udp(socket, Some(destination))) => {
+                socket.send_to(buf, *destination).map(|_| ())
+            }
+            Some(Conn::Stdout(stdout)) => {
+                let mut stdout = stdout.lock();
+                stdout.write_all(buf)?;
+                stdout.flush()
+            }
+            Some(Conn::File(file)) => file.write_all(buf),
+            _ => Err(io::Error::other("not writable")),
+        }
+    }
+
+    fn print_stats(&self) {
+        if let Some(Conn::Srt(stream)) = &self.conn {
+            match stream.bistats() {
+                Ok(stats) => eprintln!(
+                    "{}: rtt {:.1} ms, send {:.2} Mb/s, recv {:.2} Mb/s, \
+                     lost {}/{}, dropped {}/{}, retransmitted {}, buffer {} ms",
+                    self.uri,
+                    stats.msRTT,
+                    stats.mbpsSendRate,
+                    stats.mbpsRecvRate,
+                    stats.pktSndLoss,
+                    stats.pktRcvLoss,
+                    stats.pktSndDrop,
+                    stats.pktRcvDrop,
+                    stats.pktRetrans,
+                    stats.msRcvBuf.max(stats.msSndBuf),
+                ),
+                Err(e) => eprintln!("{}: no stats: {}", self.uri, e),
+            }
+        }
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let mut input = Endpoint::new(options.input, true);
+    let mut output = Endpoint::new(options.output, false);
+    input.open()?;
+    output.open()?;
+
+    let mut buf = vec![0; options.chunk_size];
+    let mut paced_since = Instant::now();
+    let mut paced_bytes = 0u64;
+    let mut next_stats = options
+        .stats_interval
+        .map(|interval| Instant::now() + interval);
+    loop {
+        if let (Some(at), Some(interval)) = (next_stats, options.stats_interval) {
+            if Instant::now() >= at {
+                input.print_stats();
+                output.print_stats();
+                next_stats = Some(at + interval);
+            }
+        }
+
+        let len = match input.read(&mut buf) {
+            Ok(0) => return Ok(()),
+            Ok(len) => len,
+            Err(e) if is_timeout(&e) => continue,
+            Err(e) if options.auto_reconnect && input.can_reconnect() => {
+                eprintln!("{}: {}, reconnecting", input.uri, e);
+                input.reconnect();
+                continue;
+            }
+            Err(e) => return Err(format!("{}: {}", input.uri, e)),
+        };
+
+        if let Err(e) = output.write(&buf[..len]) {
+            if options.auto_reconnect && output.can_reconnect() {
+                eprintln!("{}: {}, reconnecting", output.uri, e);
+                output.reconnect();
+                paced_since = Instant::now();
+                paced_bytes = 0;
+                continue;
+            }
+            return Err(format!("{}: {}", output.uri, e));
+        }
+
+        // Files and pipes can be read far faster than real time; hold the
+        // output to the given bitrate so a live receiver is not overrun.
+        if let Some(bitrate) = options.bitrate {
+            paced_bytes += len as u64;
+            let due =
+                paced_since + Duration::from_secs_f64(paced_bytes as f64 * 8.0 / bitrate as f64);
+            let now = Instant::now();
+            if due > now {
+                thread::sleep(due - now);
+            }
+        }
+    }
+}
+
+fn main() {
+    let result = parse_args(env::args().skip(1)).and_then(run);
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_ {
+        line.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn test_chunk_size_limited_for_srt_output() {
+        assert!(parse_args(args("-c 1316 udp://:5000 srt://host:9000")).is_ok());
+        assert!(parse_args(args("-c 1317 udp://:5000 srt://host:9000")).is_err());
+        assert!(parse_args(args("-c 65536 srt://:9000 udp://host:5000")).is_ok());
+    }
+}
@@ -0,0 +1,185 @@
+//! `srt-rs` URIs, in a module of their own so the fuzz targets can include
+//! the parser.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, ToSocketAddrs},
+};
+
+// Query keys each scheme understands.
+const SRT_KEYS: &[&str] = &["mode", "latency", "passphrase", "streamid"];
+const UDP_KEYS: &[&str] = &["adapter", "ttl"];
+
+pub struct Uri {
+    pub scheme: String,
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+    pub query: HashMap<String, String>,
+}
+
+impl Uri {
+    pub fn parse(text: &str) -> Result<Uri, String> {
+        if text == "-" {
+            return Uri::parse("file://con");
+        }
+        // The query may hold a passphrase, so errors leave it out.
+        let shown = text.split_once('?').map_or(text, |(base, _)| base);
+        let invalid = || format!("invalid URI {}", shown);
+        let (scheme, rest) = text.split_once("://").ok_or_else(invalid)?;
+        let (rest, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let query = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (key.to_string(), value.to_string())
+            })
+            .collect();
+        let mut uri = Uri {
+            scheme: scheme.to_string(),
+            host: String::new(),
+            port: 0,
+            path: String::new(),
+            query,
+        };
+        match scheme {
+            "file" => uri.path = rest.to_string(),
+            "srt" | "udp" => {
+                let (host, port) = rest.rsplit_once(':').ok_or_else(invalid)?;
+                uri.host = host
+                    .trim_start_matches('[')
+                    .trim_end_matches(']')
+                    .to_string();
+                uri.port = port.parse().map_err(|_| invalid())?;
+            }
+            _ => return Err(format!("unsupported scheme in {}", shown)),
+        }
+        let known = match scheme {
+            "srt" => SRT_KEYS,
+            "udp" => UDP_KEYS,
+            _ => &[],
+        };
+        if let Some(key) = uri.query.keys().find(|key| !known.contains(&key.as_str())) {
+            return Err(format!("unknown option {} in {}", key, uri));
+        }
+        match uri.query.get("mode").map(String::as_str) {
+            None | Some("caller") | Some("listener") => {}
+            Some(mode) => return Err(format!("unsupported mode {} in {}", mode, uri)),
+        }
+        Ok(uri)
+    }
+
+    /// Whether an `srt://` URI listens: with `mode=listener`, or without a
+    /// mode and host.
+    pub fn is_listener(&self) -> bool {
+        match self.query.get("mode").map(String::as_str) {
+            Some(mode) => mode == "listener",
+            None => self.host.is_empty(),
+        }
+    }
+
+    pub fn option<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>, String> {
+        match self.query.get(key) {
+            Some(value) => value
+                .parse()
+                .map(Some)
+                .map_err(|_| format!("invalid {} in {}", key, self)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn ip(&self) -> Result<IpAddr, String> {
+        if self.host.is_empty() {
+            return Ok(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+        }
+        (self.host.as_str(), self.port)
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .map(|addr| addr.ip())
+            .ok_or(format!("cannot resolve {}", self))
+    }
+}
+
+// Leaves out the query so passphrases do not end up in logs.
+impl std::fmt::Display for Uri {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.scheme.as_str() {
+            "file" => write!(f, "file://{}", self.path),
+            _ => write!(f, "{}://{}:{}", self.scheme, self.host, self.port),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Uri;
+
+    #[test]
+    fn test_parse() {
+        let uri = Uri::parse("srt://example.com:9000?mode=caller&latency=200&streamid=live")
+            .expect("fail parse()");
+        assert_eq!(uri.scheme, "srt");
+        assert_eq!(uri.host, "example.com");
+        assert_eq!(uri.port, 9000);
+        assert_eq!(uri.option::<i32>("latency"), Ok(Some(200)));
+        assert_eq!(
+            uri.option::<String>("streamid"),
+            Ok(Some("live".to_string()))
+        );
+        assert_eq!(uri.option::<String>("passphrase"), Ok(None));
+        assert!(!uri.is_listener());
+        assert!(Uri::parse("srt://:9000")
+            .expect("fail parse()")
+            .is_listener());
+        assert!(Uri::parse("srt://example.com:9000?mode=listener")
+            .expect("fail parse()")
+            .is_listener());
+
+        let uri = Uri::parse("udp://[ff02::1]:5000?ttl=4").expect("fail parse()");
+        assert_eq!(uri.host, "ff02::1");
+        assert_eq!(uri.port, 5000);
+        assert_eq!(uri.option::<u32>("ttl"), Ok(Some(4)));
+
+        assert_eq!(
+            Uri::parse("file:///tmp/in.ts").expect("fail parse()").path,
+            "/tmp/in.ts"
+        );
+        assert_eq!(Uri::parse("-").expect("fail parse()").path, "con");
+    }
+
+    #[test]
+    fn test_parse_rejected() {
+        for text in &[
+            "srt://host:9000?mode=rendezvous",
+            "srt://host:port",
+            "srt://host:65536",
+            "srt://host",
+            "srt://host:9000?laytency=200",
+            "udp://host:5000?latency=200",
+            "file://in.ts?mode=caller",
+            "rtmp://host:1935",
+            "host:9000",
+        ] {
+            assert!(Uri::parse(text).is_err(), "{} was accepted", text);
+        }
+        let uri = Uri::parse("srt://host:9000?latency=soon").expect("fail parse()");
+        assert!(uri.option::<i32>("latency").is_err());
+    }
+
+    #[test]
+    fn test_display_hides_query() {
+        let uri = Uri::parse("srt://host:9000?passphrase=secret-passphrase").expect("fail parse()");
+        assert_eq!(uri.to_string(), "srt://host:9000");
+        for text in &[
+            "srt://host:9000?passphrase=secret-passphrase&bad=1",
+            "srt://host:bad?passphrase=secret-passphrase",
+            "srt:/host:9000?passphrase=secret-passphrase",
+            "rtmp://host:1935?passphrase=secret-passphrase",
+        ] {
+            let error = Uri::parse(text).err().expect("invalid URI accepted");
+            assert!(!error.contains("secret"), "{}", error);
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Routes libsrt's connect hook to Rust closures. libsrt calls the hook of a
+//! non-blocking caller once per failed connection attempt; for a group it is
+//! called for every member.
+
+use crate::{
+    error::{self, SrtError, SrtErrorKind, SrtRejectReason},
+    socket::{SrtSocket, SrtSocketStatus},
+};
+
+use libsrt_sys as srt;
+use os_socketaddr::OsSocketAddr;
+
+use std::{
+    net::SocketAddr,
+    os::raw::{c_int, c_void},
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::Waker,
+};
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+pub(crate) type ConnectCallback = Arc<dyn Fn(&ConnectEvent) + Send + Sync>;
+
+/// The outcome of one connection attempt.
+#[derive(Clone, Debug)]
+pub struct ConnectEvent {
+    /// The socket the attempt was made on; for a group, the member.
+    pub socket: SrtSocket,
+    /// A rejection carries the reason, as `SrtErrorKind::ConnRej`.
+    pub result: Result<()>,
+    pub peer: Option<SocketAddr>,
+    /// The token of the group member, or -1.
+    pub token: i32,
+}
+
+/// Where a non-blocking connection attempt stands.
+#[derive(Clone, Debug)]
+pub enum ConnectState {
+    Connecting,
+    Connected,
+    Failed(SrtError),
+}
+
+#[derive(Default)]
+pub(crate) struct Outcome {
+    failure: Option<SrtError>,
+    pub(crate) waker: Option<Waker>,
+}
+
+pub(crate) type Watch = Arc<Mutex<Outcome>>;
+
+pub(crate) fn lock(watch: &Watch) -> MutexGuard<'_, Outcome> {
+    watch.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Records the first failure reported on `socket` and wakes the stored
+/// waker on every report. Must be done before connecting.
+pub(crate) fn watch(socket: &SrtSocket) -> Result<Watch> {
+    let watch = Watch::default();
+    let outcome = watch.clone();
+    add(
+        socket,
+        Arc::new(move |event: &ConnectEvent| {
+            let mut outcome = lock(&outcome);
+            if let Err(e) = &event.result {
+                outcome.failure.get_or_insert_with(|| e.clone());
+            }
+            if let Some(waker) = outcome.waker.take() {
+                waker.wake();
+            }
+        }),
+    )?;
+    Ok(watch)
+}
+
+/// The state of the watched `socket`. A reported failure takes precedence
+/// over the socket status, which libsrt may change before reporting.
+pub(crate) fn state(socket: &SrtSocket, watch: &Watch) -> ConnectState {
+    if let Some(e) = &lock(watch).failure {
+        return ConnectState::Failed(e.clone());
+    }
+    let status = match socket.get_socket_state() {
+        Ok(status) => status,
+        Err(e) => return ConnectState::Failed(e),
+    };
+    let e = match status {
+        SrtSocketStatus::Connected => return ConnectState::Connected,
+        SrtSocketStatus::Connecting => return ConnectState::Connecting,
+        // Broken before the callback ran; the reject reason is set by then.
+        SrtSocketStatus::Broken => match &lock(watch).failure {
+            Some(e) => e.clone(),
+            None => match socket.get_reject_reason() {
+                SrtRejectReason::Unknown => SrtErrorKind::ConnLost.into(),
+                r => SrtErrorKind::ConnRej(r).into(),
+            },
+        },
+        SrtSocketStatus::Init => SrtErrorKind::UnboundSock.into(),
+        SrtSocketStatus::Opened => SrtErrorKind::InvOp.into(),
+        SrtSocketStatus::Listening => SrtErrorKind::InvOp.into(),
+        SrtSocketStatus::Closing => SrtErrorKind::Closed.into(),
+        SrtSocketStatus::Closed => SrtErrorKind::Closed.into(),
+        SrtSocketStatus::NonExist => SrtErrorKind::InvSock.into(),
+    };
+    ConnectState::Failed(e)
+}
+
+static CALLBACKS: Mutex<Vec<(i32, ConnectCallback)>> = Mutex::new(Vec::new());
+
+fn callbacks_lock() -> MutexGuard<'static, Vec<(i32, ConnectCallback)>> {
+    CALLBACKS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Calls `callback` for every attempt reported on `socket`, after the
+/// callbacks added before. Must be done before connecting.
+pub(crate) fn add(socket: &SrtSocket, callback: ConnectCallback) -> Result<()> {
+    let result = unsafe {
+        srt::srt_connect_callback(
+            socket.id,
+            Some(on_connect),
+            socket.id as isize as *mut c_void,
+        )
+    };
+    // Nothing is reported before connecting, so registering last is early
+    // enough.
+    error::handle_result((), result)?;
+    callbacks_lock().push((socket.id, callback));
+    Ok(())
+}
+
+pub(crate) fn remove(socket: &SrtSocket) {
+    callbacks_lock().retain(|(id, _)| *id != socket.id);
+}
+
+// The opaque pointer is the ID of the socket the hook was set on, so nothing
+// has to outlive the socket on the libsrt side.
+unsafe extern "C" fn on_connect(
+    opaque: *mut c_void,
+    ns: srt::SRTSOCKET,
+    errorcode: c_int,
+    peeraddr: *const srt::sockaddr,
+    token: c_int,
+) {
+    let id = opaque as isize as i32;
+    let callbacks: Vec<_> = callbacks_lock()
+        .iter()
+        .filter(|(registered, _)| *registered == id)
+        .map(|(_, callback)| callback.clone())
+        .collect();
+    if callbacks.is_empty() {
+        return;
+    }
+    let socket = SrtSocket { id: ns };
+    let result = match srt::SRT_ERRNO(errorcode) {
+        srt::SRT_ERRNO::SRT_SUCCESS => Ok(()),
+        srt::SRT_ERRNO::SRT_ECONNREJ => {
+            Err(SrtErrorKind::ConnRej(socket.get_reject_reason()).into())
+        }
+        e => Err(SrtErrorKind::from(e).into()),
+    };
+    // libsrt keeps peer addresses in a union large enough for IPv6.
+    let peer = OsSocketAddr::copy_from_raw(peeraddr as *const _, OsSocketAddr::new().capacity())
+        .into_addr();
+    let event = ConnectEvent {
+        socket,
+        result,
+        peer,
+        token,
+    };
+    // Unwinding into libsrt is undefined behaviour.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        for callback in callbacks {
+            callback(&event);
+        }
+    }));
+}
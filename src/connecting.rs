@@ -0,0 +1,82 @@
+use crate::{
+    connect_callback::{self, ConnectState, Watch},
+    error::{SrtError, SrtErrorKind},
+    socket::SrtSocket,
+    Epoll, SrtStream,
+};
+
+use libsrt_sys as srt;
+
+use std::time::{Duration, Instant};
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+/// A connection being set up without blocking, for event loops that do not
+/// use futures. Dropping it abandons the attempt.
+pub struct SrtConnecting {
+    socket: Option<SrtSocket>,
+    watch: Watch,
+}
+
+impl SrtConnecting {
+    /// Must run before connecting, see `connect_callback::watch`.
+    pub(crate) fn watch(socket: SrtSocket) -> Result<Self> {
+        socket.set_receive_blocking(false)?;
+        Ok(Self {
+            socket: Some(socket),
+            watch: connect_callback::watch(&socket)?,
+        })
+    }
+    /// The underlying socket, e.g. to wait on many attempts with one epoll.
+    /// It becomes writable once connected.
+    pub fn socket(&self) -> SrtSocket {
+        self.socket.unwrap()
+    }
+    pub fn poll_state(&self) -> ConnectState {
+        connect_callback::state(&self.socket(), &self.watch)
+    }
+    /// Blocks until the attempt is decided or `timeout` passed, forever if
+    /// `None`. Returns `Connecting` on timeout.
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<ConnectState> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        let mut epoll = Epoll::new()?;
+        let events = srt::SRT_EPOLL_OPT::SRT_EPOLL_OUT | srt::SRT_EPOLL_OPT::SRT_EPOLL_ERR;
+        epoll.add(&self.socket(), &events)?;
+        loop {
+            let state = self.poll_state();
+            let timeout = match deadline {
+                Some(deadline) => deadline
+                    .saturating_duration_since(Instant::now())
+                    .as_millis() as i64,
+                None => -1,
+            };
+            if !matches!(state, ConnectState::Connecting) || timeout == 0 {
+                return Ok(state);
+            }
+            epoll.wait(timeout)?;
+        }
+    }
+    /// The connected stream, blocking again; fails unless `poll_state` says
+    /// `Connected`.
+    pub fn into_stream(mut self) -> Result<SrtStream> {
+        match self.poll_state() {
+            ConnectState::Connected => {}
+            ConnectState::Connecting => return Err(SrtErrorKind::NoConn.into()),
+            ConnectState::Failed(e) => return Err(e),
+        }
+        let socket = self.socket.take().unwrap();
+        let stream = SrtStream { socket };
+        socket.set_receive_blocking(true)?;
+        Ok(stream)
+    }
+    /// Abandons the attempt and closes the socket.
+    pub fn cancel(mut self) -> Result<()> {
+        self.socket.take().unwrap().close()
+    }
+}
+
+impl Drop for SrtConnecting {
+    fn drop(&mut self) {
+        self.socket.take().map(|socket| socket.close());
+    }
+}
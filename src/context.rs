@@ -0,0 +1,71 @@
+use crate::error::{self, SrtError, SrtErrorKind};
+
+use libsrt_sys as srt;
+
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+// Number of live `SrtContext`s, unmatched `startup()` calls and open sockets.
+// libsrt is started when this leaves zero and cleaned up when it returns to it.
+static USERS: Mutex<usize> = Mutex::new(0);
+
+fn users_lock() -> MutexGuard<'static, usize> {
+    USERS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(crate) fn retain() -> Result<()> {
+    let mut users = users_lock();
+    if *users == 0 {
+        let result = unsafe { srt::srt_startup() };
+        if result < 0 {
+            return error::handle_result((), result);
+        }
+    }
+    *users += 1;
+    Ok(())
+}
+
+pub(crate) fn release() -> Result<()> {
+    let mut users = users_lock();
+    match *users {
+        0 => Err(SrtErrorKind::InvOp.into()),
+        1 => {
+            *users = 0;
+            let result = unsafe { srt::srt_cleanup() };
+            error::handle_result((), result)
+        }
+        _ => {
+            *users -= 1;
+            Ok(())
+        }
+    }
+}
+
+/// Keeps libsrt initialized for as long as it, one of its clones, or any
+/// socket is alive. Creating sockets does not require holding one; it is for
+/// callers that want the library to stay up between sockets.
+pub struct SrtContext {
+    _priv: (),
+}
+
+impl SrtContext {
+    pub fn new() -> Result<Self> {
+        retain()?;
+        Ok(Self { _priv: () })
+    }
+}
+
+impl Clone for SrtContext {
+    fn clone(&self) -> Self {
+        // The count is non-zero while `self` exists, so this never starts up.
+        retain().expect("libsrt context already started");
+        Self { _priv: () }
+    }
+}
+
+impl Drop for SrtContext {
+    fn drop(&mut self) {
+        let _ = release();
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{
+    error::SrtError,
+    socket::{SrtKmState, SrtSocket},
+};
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum EncryptionEvent {
+    Secured,
+    /// Enough packets went by for libsrt to have switched keys, going by the
+    /// KM refresh rate. This is an estimate: libsrt does not report the
+    /// switch itself, and the packet count is the larger of sent and received.
+    KeyRefreshDue,
+    /// The peer uses a different passphrase, or packets stopped decrypting.
+    BadSecret,
+    /// The peer is encrypted but no passphrase was set here, or vice versa.
+    NoSecret,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EncryptionHealth {
+    pub send_km_state: SrtKmState,
+    pub receive_km_state: SrtKmState,
+    pub undecrypted_packets: i32,
+    pub undecrypted_bytes: u64,
+}
+
+impl EncryptionHealth {
+    pub fn of(socket: &SrtSocket) -> Result<Self> {
+        let stats = socket.bistats_total()?;
+        Ok(Self {
+            send_km_state: socket.get_send_km_state()?,
+            receive_km_state: socket.get_receive_km_state()?,
+            undecrypted_packets: stats.pktRcvUndecryptTotal,
+            undecrypted_bytes: stats.byteRcvUndecryptTotal,
+        })
+    }
+    fn has_state(&self, state: SrtKmState) -> bool {
+        self.send_km_state == state || self.receive_km_state == state
+    }
+}
+
+/// Turns periodic `EncryptionHealth` samples of one socket into events.
+///
+/// Each condition is reported once when it starts. libsrt does not expose key
+/// switches directly, so `KeyRefreshDue` is estimated from the packet counters
+/// and the socket's KM refresh rate.
+pub struct EncryptionMonitor {
+    socket: SrtSocket,
+    refresh_rate: Option<i64>,
+    last: Option<EncryptionHealth>,
+    last_event: Option<EncryptionEvent>,
+    refreshes: i64,
+}
+
+impl EncryptionMonitor {
+    pub fn new(socket: SrtSocket) -> Self {
+        Self {
+            socket,
+            refresh_rate: None,
+            last: None,
+            last_event: None,
+            refreshes: 0,
+        }
+    }
+    pub fn health(&self) -> Option<EncryptionHealth> {
+        self.last
+    }
+    pub fn poll(&mut self) -> Result<Vec<EncryptionEvent>> {
+        let health = EncryptionHealth::of(&self.socket)?;
+        let undecrypting = match self.last {
+            Some(last) => health.undecrypted_packets > last.undecrypted_packets,
+            None => health.undecrypted_packets > 0,
+        };
+        let state = if undecrypting || health.has_state(SrtKmState::BadSecret) {
+            Some(EncryptionEvent::BadSecret)
+        } else if health.has_state(SrtKmState::NoSecret) {
+            Some(EncryptionEvent::NoSecret)
+        } else if health.has_state(SrtKmState::Secured) {
+            Some(EncryptionEvent::Secured)
+        } else {
+            None
+        };
+        let mut events = Vec::new();
+        if state.is_some() && state != self.last_event {
+            events.extend(state);
+        }
+        if state == Some(EncryptionEvent::Secured) {
+            let refreshes = self.count_refreshes()?;
+            if self.last_event == Some(EncryptionEvent::Secured) && refreshes > self.refreshes {
+                events.push(EncryptionEvent::KeyRefreshDue);
+            }
+            self.refreshes = refreshes;
+        }
+        self.last = Some(health);
+        self.last_event = state;
+        Ok(events)
+    }
+    fn count_refreshes(&mut self) -> Result<i64> {
+        let refresh_rate = match self.refresh_rate {
+            Some(rate) => rate,
+            None => {
+                let rate = self.socket.get_km_refresh_rate()? as i64;
+                self.refresh_rate = Some(rate);
+                rate
+            }
+        };
+        if refresh_rate <= 0 {
+            return Ok(0);
+        }
+        let stats = self.socket.bistats_total()?;
+        let packets = stats.pktSentUniqueTotal.max(stats.pktRecvUniqueTotal);
+        Ok(packets / refresh_rate)
+    }
+}
@@ -3,13 +3,24 @@ use libsrt_sys as srt;
 use std::{
     convert::From,
     error::Error,
+    ffi::CStr,
     fmt::{self, Display, Formatter},
     io::{self, ErrorKind},
     os::raw::c_int,
+    sync::Arc,
 };
 
-#[derive(Clone, Copy, Debug)]
-pub enum SrtError {
+/// An error with what libsrt reported along with it: the system error behind
+/// it, if any, as `source()`, and libsrt's own message.
+#[derive(Clone, Debug)]
+pub struct SrtError {
+    kind: SrtErrorKind,
+    sys_error: Option<Arc<io::Error>>,
+    message: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SrtErrorKind {
     Unknown,
     Success,
     ConnSetup,
@@ -51,178 +62,265 @@ pub enum SrtError {
     Timeout,
     Congest,
     PeerErr,
+    /// A code this version of srt-rs does not know.
+    Other(i32),
+}
+
+impl SrtError {
+    pub fn kind(&self) -> SrtErrorKind {
+        self.kind
+    }
+    /// The `errno` of the failed system call, or its Windows equivalent.
+    pub fn sys_errno(&self) -> Option<i32> {
+        self.sys_error.as_ref().and_then(|e| e.raw_os_error())
+    }
+    /// libsrt's description, more specific than that of the kind.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+    pub(crate) fn with_kind(self, kind: SrtErrorKind) -> Self {
+        Self { kind, ..self }
+    }
+    pub(crate) fn with_message(self, message: &str) -> Self {
+        Self {
+            message: Some(message.to_string()),
+            ..self
+        }
+    }
+}
+
+impl From<SrtErrorKind> for SrtError {
+    fn from(kind: SrtErrorKind) -> Self {
+        Self {
+            kind,
+            sys_error: None,
+            message: None,
+        }
+    }
 }
 
 impl Display for SrtError {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", error_msg(self))
+        match &self.message {
+            Some(message) => write!(f, "{}", message),
+            None => write!(f, "{}", self.kind),
+        }
+    }
+}
+
+impl Error for SrtError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.sys_error
+            .as_ref()
+            .map(|e| e.as_ref() as &(dyn Error + 'static))
     }
 }
 
-impl Error for SrtError {}
+impl Display for SrtErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", error_msg(self))
+    }
+}
 
 pub fn handle_result<T>(ok: T, return_code: i32) -> Result<T, SrtError> {
     match return_code {
         0 => Ok(ok),
         -1 => Err(get_last_error()),
-        e => unreachable!("unrecognized return code {}", e),
+        _ => Err(SrtErrorKind::Unknown.into()),
     }
 }
 
 pub fn get_last_error() -> SrtError {
-    let mut _errno_loc = 0;
-    let err_no = unsafe { srt::srt_getlasterror(&mut _errno_loc as *mut c_int) };
-    let err = srt::SRT_ERRNO(err_no);
-    SrtError::from(err)
+    let mut errno_loc = 0;
+    let err_no = unsafe { srt::srt_getlasterror(&mut errno_loc as *mut c_int) };
+    // Only valid until the next libsrt call on this thread.
+    let message = unsafe { srt::srt_getlasterror_str() };
+    let message = if message.is_null() {
+        None
+    } else {
+        Some(
+            unsafe { CStr::from_ptr(message) }
+                .to_string_lossy()
+                .into_owned(),
+        )
+    };
+    SrtError {
+        kind: SrtErrorKind::from(srt::SRT_ERRNO(err_no)),
+        // 0 unless a system call failed.
+        sys_error: match errno_loc {
+            0 => None,
+            errno => Some(Arc::new(io::Error::from_raw_os_error(errno))),
+        },
+        message,
+    }
 }
 
 impl From<SrtError> for io::Error {
     fn from(e: SrtError) -> Self {
-        io::Error::new(
-            match e {
-                SrtError::Unknown => ErrorKind::Other,
-                SrtError::Success => ErrorKind::Other,
-                SrtError::ConnSetup => ErrorKind::ConnectionRefused,
-                SrtError::NoServer => ErrorKind::ConnectionRefused,
-                SrtError::ConnRej(_) => ErrorKind::ConnectionRefused,
-                SrtError::SockFail => ErrorKind::AddrNotAvailable,
-                SrtError::SecFail => ErrorKind::ConnectionRefused,
-                SrtError::ConnFail => ErrorKind::ConnectionRefused,
-                SrtError::Closed => ErrorKind::AddrNotAvailable,
-                SrtError::ConnLost => ErrorKind::ConnectionAborted,
-                SrtError::NoConn => ErrorKind::NotConnected,
-                SrtError::Resource => ErrorKind::Other,
-                SrtError::Thread => ErrorKind::Other,
-                SrtError::NoBuf => ErrorKind::Other,
-                SrtError::SysObj => ErrorKind::Other,
-                SrtError::File => ErrorKind::NotFound,
-                SrtError::InvRdOff => ErrorKind::InvalidInput,
-                SrtError::RdPerm => ErrorKind::PermissionDenied,
-                SrtError::InvWrOff => ErrorKind::InvalidInput,
-                SrtError::WrPerm => ErrorKind::PermissionDenied,
-                SrtError::InvOp => ErrorKind::InvalidInput,
-                SrtError::BoundSock => ErrorKind::AddrInUse,
-                SrtError::ConnSock => ErrorKind::AddrInUse,
-                SrtError::InvParam => ErrorKind::InvalidInput,
-                SrtError::InvSock => ErrorKind::AddrNotAvailable,
-                SrtError::UnboundSock => ErrorKind::NotConnected,
-                SrtError::NoListen => ErrorKind::InvalidInput,
-                SrtError::RdvNoServ => ErrorKind::ConnectionRefused,
-                SrtError::RdvUnbound => ErrorKind::ConnectionRefused,
-                SrtError::InvalMsgApi => ErrorKind::InvalidInput,
-                SrtError::InvalBufferApi => ErrorKind::InvalidInput,
-                SrtError::DupListen => ErrorKind::AddrInUse,
-                SrtError::LargeMsg => ErrorKind::Other,
-                SrtError::InvPollId => ErrorKind::AddrNotAvailable,
-                SrtError::PollEmpty => ErrorKind::Other,
-                SrtError::AsyncFail => ErrorKind::WouldBlock,
-                SrtError::AsyncSnd => ErrorKind::WouldBlock,
-                SrtError::AsyncRcv => ErrorKind::WouldBlock,
-                SrtError::Timeout => ErrorKind::TimedOut,
-                SrtError::Congest => ErrorKind::Other,
-                SrtError::PeerErr => ErrorKind::Other,
-            },
-            e,
-        )
+        // libsrt records errno even for failures of its own, when it may be
+        // stale, so the system error only refines kinds SRT does not map and
+        // failures to set up the UDP socket, e.g. an address in use.
+        let srt_kind = e.kind.io_error_kind();
+        let sys_kind = e
+            .sys_error
+            .as_ref()
+            .map(|sys| sys.kind())
+            .filter(|kind| *kind != ErrorKind::Other);
+        let kind = match (e.kind, sys_kind) {
+            (SrtErrorKind::SockFail, Some(kind)) => kind,
+            (_, Some(kind)) if srt_kind == ErrorKind::Other => kind,
+            _ => srt_kind,
+        };
+        io::Error::new(kind, e)
+    }
+}
+
+impl SrtErrorKind {
+    fn io_error_kind(self) -> ErrorKind {
+        match self {
+            SrtErrorKind::Unknown => ErrorKind::Other,
+            SrtErrorKind::Success => ErrorKind::Other,
+            SrtErrorKind::ConnSetup => ErrorKind::ConnectionRefused,
+            SrtErrorKind::NoServer => ErrorKind::ConnectionRefused,
+            SrtErrorKind::ConnRej(_) => ErrorKind::ConnectionRefused,
+            SrtErrorKind::SockFail => ErrorKind::AddrNotAvailable,
+            SrtErrorKind::SecFail => ErrorKind::ConnectionRefused,
+            SrtErrorKind::ConnFail => ErrorKind::ConnectionRefused,
+            SrtErrorKind::Closed => ErrorKind::AddrNotAvailable,
+            SrtErrorKind::ConnLost => ErrorKind::ConnectionAborted,
+            SrtErrorKind::NoConn => ErrorKind::NotConnected,
+            SrtErrorKind::Resource => ErrorKind::Other,
+            SrtErrorKind::Thread => ErrorKind::Other,
+            SrtErrorKind::NoBuf => ErrorKind::Other,
+            SrtErrorKind::SysObj => ErrorKind::Other,
+            SrtErrorKind::File => ErrorKind::NotFound,
+            SrtErrorKind::InvRdOff => ErrorKind::InvalidInput,
+            SrtErrorKind::RdPerm => ErrorKind::PermissionDenied,
+            SrtErrorKind::InvWrOff => ErrorKind::InvalidInput,
+            SrtErrorKind::WrPerm => ErrorKind::PermissionDenied,
+            SrtErrorKind::InvOp => ErrorKind::InvalidInput,
+            SrtErrorKind::BoundSock => ErrorKind::AddrInUse,
+            SrtErrorKind::ConnSock => ErrorKind::AddrInUse,
+            SrtErrorKind::InvParam => ErrorKind::InvalidInput,
+            SrtErrorKind::InvSock => ErrorKind::AddrNotAvailable,
+            SrtErrorKind::UnboundSock => ErrorKind::NotConnected,
+            SrtErrorKind::NoListen => ErrorKind::InvalidInput,
+            SrtErrorKind::RdvNoServ => ErrorKind::ConnectionRefused,
+            SrtErrorKind::RdvUnbound => ErrorKind::ConnectionRefused,
+            SrtErrorKind::InvalMsgApi => ErrorKind::InvalidInput,
+            SrtErrorKind::InvalBufferApi => ErrorKind::InvalidInput,
+            SrtErrorKind::DupListen => ErrorKind::AddrInUse,
+            SrtErrorKind::LargeMsg => ErrorKind::Other,
+            SrtErrorKind::InvPollId => ErrorKind::AddrNotAvailable,
+            SrtErrorKind::PollEmpty => ErrorKind::Other,
+            SrtErrorKind::AsyncFail => ErrorKind::WouldBlock,
+            SrtErrorKind::AsyncSnd => ErrorKind::WouldBlock,
+            SrtErrorKind::AsyncRcv => ErrorKind::WouldBlock,
+            SrtErrorKind::Timeout => ErrorKind::TimedOut,
+            SrtErrorKind::Congest => ErrorKind::Other,
+            SrtErrorKind::PeerErr => ErrorKind::Other,
+            SrtErrorKind::Other(_) => ErrorKind::Other,
+        }
     }
 }
 
-impl From<srt::SRT_ERRNO> for SrtError {
+impl From<srt::SRT_ERRNO> for SrtErrorKind {
     fn from(err_no: srt::SRT_ERRNO) -> Self {
         match err_no {
-            srt::SRT_ERRNO::SRT_EUNKNOWN => SrtError::Unknown,
-            srt::SRT_ERRNO::SRT_SUCCESS => SrtError::Success,
-            srt::SRT_ERRNO::SRT_ECONNSETUP => SrtError::ConnSetup,
-            srt::SRT_ERRNO::SRT_ENOSERVER => SrtError::NoServer,
-            srt::SRT_ERRNO::SRT_ECONNREJ => SrtError::ConnRej(SrtRejectReason::Unknown),
-            srt::SRT_ERRNO::SRT_ESOCKFAIL => SrtError::SockFail,
-            srt::SRT_ERRNO::SRT_ESECFAIL => SrtError::SecFail,
-            srt::SRT_ERRNO::SRT_ESCLOSED => SrtError::Closed,
-            srt::SRT_ERRNO::SRT_ECONNFAIL => SrtError::ConnFail,
-            srt::SRT_ERRNO::SRT_ECONNLOST => SrtError::ConnLost,
-            srt::SRT_ERRNO::SRT_ENOCONN => SrtError::NoConn,
-            srt::SRT_ERRNO::SRT_ERESOURCE => SrtError::Resource,
-            srt::SRT_ERRNO::SRT_ETHREAD => SrtError::Thread,
-            srt::SRT_ERRNO::SRT_ENOBUF => SrtError::NoBuf,
-            srt::SRT_ERRNO::SRT_ESYSOBJ => SrtError::SysObj,
-            srt::SRT_ERRNO::SRT_EFILE => SrtError::File,
-            srt::SRT_ERRNO::SRT_EINVRDOFF => SrtError::InvRdOff,
-            srt::SRT_ERRNO::SRT_ERDPERM => SrtError::RdPerm,
-            srt::SRT_ERRNO::SRT_EINVWROFF => SrtError::InvWrOff,
-            srt::SRT_ERRNO::SRT_EWRPERM => SrtError::WrPerm,
-            srt::SRT_ERRNO::SRT_EINVOP => SrtError::InvOp,
-            srt::SRT_ERRNO::SRT_EBOUNDSOCK => SrtError::BoundSock,
-            srt::SRT_ERRNO::SRT_ECONNSOCK => SrtError::ConnSock,
-            srt::SRT_ERRNO::SRT_EINVPARAM => SrtError::InvParam,
-            srt::SRT_ERRNO::SRT_EINVSOCK => SrtError::InvSock,
-            srt::SRT_ERRNO::SRT_EUNBOUNDSOCK => SrtError::UnboundSock,
-            srt::SRT_ERRNO::SRT_ENOLISTEN => SrtError::NoListen,
-            srt::SRT_ERRNO::SRT_ERDVNOSERV => SrtError::RdvNoServ,
-            srt::SRT_ERRNO::SRT_ERDVUNBOUND => SrtError::RdvUnbound,
-            srt::SRT_ERRNO::SRT_EINVALMSGAPI => SrtError::InvalMsgApi,
-            srt::SRT_ERRNO::SRT_EINVALBUFFERAPI => SrtError::InvalBufferApi,
-            srt::SRT_ERRNO::SRT_EDUPLISTEN => SrtError::DupListen,
-            srt::SRT_ERRNO::SRT_ELARGEMSG => SrtError::LargeMsg,
-            srt::SRT_ERRNO::SRT_EINVPOLLID => SrtError::InvPollId,
-            srt::SRT_ERRNO::SRT_EPOLLEMPTY => SrtError::PollEmpty,
-            srt::SRT_ERRNO::SRT_EASYNCFAIL => SrtError::AsyncFail,
-            srt::SRT_ERRNO::SRT_EASYNCSND => SrtError::AsyncSnd,
-            srt::SRT_ERRNO::SRT_EASYNCRCV => SrtError::AsyncRcv,
-            srt::SRT_ERRNO::SRT_ETIMEOUT => SrtError::Timeout,
-            srt::SRT_ERRNO::SRT_ECONGEST => SrtError::Congest,
-            srt::SRT_ERRNO::SRT_EPEERERR => SrtError::PeerErr,
-            _ => unreachable!("unrecognized error no"),
+            srt::SRT_ERRNO::SRT_EUNKNOWN => SrtErrorKind::Unknown,
+            srt::SRT_ERRNO::SRT_SUCCESS => SrtErrorKind::Success,
+            srt::SRT_ERRNO::SRT_ECONNSETUP => SrtErrorKind::ConnSetup,
+            srt::SRT_ERRNO::SRT_ENOSERVER => SrtErrorKind::NoServer,
+            srt::SRT_ERRNO::SRT_ECONNREJ => SrtErrorKind::ConnRej(SrtRejectReason::Unknown),
+            srt::SRT_ERRNO::SRT_ESOCKFAIL => SrtErrorKind::SockFail,
+            srt::SRT_ERRNO::SRT_ESECFAIL => SrtErrorKind::SecFail,
+            srt::SRT_ERRNO::SRT_ESCLOSED => SrtErrorKind::Closed,
+            srt::SRT_ERRNO::SRT_ECONNFAIL => SrtErrorKind::ConnFail,
+            srt::SRT_ERRNO::SRT_ECONNLOST => SrtErrorKind::ConnLost,
+            srt::SRT_ERRNO::SRT_ENOCONN => SrtErrorKind::NoConn,
+            srt::SRT_ERRNO::SRT_ERESOURCE => SrtErrorKind::Resource,
+            srt::SRT_ERRNO::SRT_ETHREAD => SrtErrorKind::Thread,
+            srt::SRT_ERRNO::SRT_ENOBUF => SrtErrorKind::NoBuf,
+            srt::SRT_ERRNO::SRT_ESYSOBJ => SrtErrorKind::SysObj,
+            srt::SRT_ERRNO::SRT_EFILE => SrtErrorKind::File,
+            srt::SRT_ERRNO::SRT_EINVRDOFF => SrtErrorKind::InvRdOff,
+            srt::SRT_ERRNO::SRT_ERDPERM => SrtErrorKind::RdPerm,
+            srt::SRT_ERRNO::SRT_EINVWROFF => SrtErrorKind::InvWrOff,
+            srt::SRT_ERRNO::SRT_EWRPERM => SrtErrorKind::WrPerm,
+            srt::SRT_ERRNO::SRT_EINVOP => SrtErrorKind::InvOp,
+            srt::SRT_ERRNO::SRT_EBOUNDSOCK => SrtErrorKind::BoundSock,
+            srt::SRT_ERRNO::SRT_ECONNSOCK => SrtErrorKind::ConnSock,
+            srt::SRT_ERRNO::SRT_EINVPARAM => SrtErrorKind::InvParam,
+            srt::SRT_ERRNO::SRT_EINVSOCK => SrtErrorKind::InvSock,
+            srt::SRT_ERRNO::SRT_EUNBOUNDSOCK => SrtErrorKind::UnboundSock,
+            srt::SRT_ERRNO::SRT_ENOLISTEN => SrtErrorKind::NoListen,
+            srt::SRT_ERRNO::SRT_ERDVNOSERV => SrtErrorKind::RdvNoServ,
+            srt::SRT_ERRNO::SRT_ERDVUNBOUND => SrtErrorKind::RdvUnbound,
+            srt::SRT_ERRNO::SRT_EINVALMSGAPI => SrtErrorKind::InvalMsgApi,
+            srt::SRT_ERRNO::SRT_EINVALBUFFERAPI => SrtErrorKind::InvalBufferApi,
+            srt::SRT_ERRNO::SRT_EDUPLISTEN => SrtErrorKind::DupListen,
+            srt::SRT_ERRNO::SRT_ELARGEMSG => SrtErrorKind::LargeMsg,
+            srt::SRT_ERRNO::SRT_EINVPOLLID => SrtErrorKind::InvPollId,
+            srt::SRT_ERRNO::SRT_EPOLLEMPTY => SrtErrorKind::PollEmpty,
+            srt::SRT_ERRNO::SRT_EASYNCFAIL => SrtErrorKind::AsyncFail,
+            srt::SRT_ERRNO::SRT_EASYNCSND => SrtErrorKind::AsyncSnd,
+            srt::SRT_ERRNO::SRT_EASYNCRCV => SrtErrorKind::AsyncRcv,
+            srt::SRT_ERRNO::SRT_ETIMEOUT => SrtErrorKind::Timeout,
+            srt::SRT_ERRNO::SRT_ECONGEST => SrtErrorKind::Congest,
+            srt::SRT_ERRNO::SRT_EPEERERR => SrtErrorKind::PeerErr,
+            srt::SRT_ERRNO(code) => SrtErrorKind::Other(code),
         }
     }
 }
 
-fn error_msg(err: &SrtError) -> String {
+fn error_msg(err: &SrtErrorKind) -> String {
     match err {
-        SrtError::Unknown => "Internal error when setting the right error code".to_string(),
-        SrtError::Success => "The value set when the last error was cleared and no error has occurred since then".to_string(),
-        SrtError::ConnSetup => "General setup error resulting from internal system state".to_string(),
-        SrtError::NoServer => "Connection timed out while attempting to connect to the remote address".to_string(),
-        SrtError::ConnRej(reason) => format!("Connection has been rejected: {:?}", reason),
-        SrtError::SockFail => "An error occurred when trying to call a system function on an internally used UDP socket".to_string(),
-        SrtError::SecFail => "A possible tampering with the handshake packets was detected, or encryption request wasn't properly fulfilled.".to_string(),
-        SrtError::Closed => "A socket that was vital for an operation called in blocking mode has been closed during the operation".to_string(),
-        SrtError::ConnFail => "General connection failure of unknown details".to_string(),
-        SrtError::ConnLost => "The socket was properly connected, but the connection has been broken".to_string(),
-        SrtError::NoConn => "The socket is not connected".to_string(),
-        SrtError::Resource => "System or standard library error reported unexpectedly for unknown purpose".to_string(),
-        SrtError::Thread => "System was unable to spawn a new thread when requried".to_string(),
-        SrtError::NoBuf => "System was unable to allocate memory for buffers".to_string(),
-        SrtError::SysObj => "System was unable to allocate system specific objects".to_string(),
-        SrtError::File => "General filesystem error (for functions operating with file transmission)".to_string(),
-        SrtError::InvRdOff => "Failure when trying to read from a given position in the file".to_string(),
-        SrtError::RdPerm => "Read permission was denied when trying to read from file".to_string(),
-        SrtError::InvWrOff => "Failed to set position in the written file".to_string(),
-        SrtError::WrPerm => "Write permission was denied when trying to write to a file".to_string(),
-        SrtError::InvOp => "Invalid operation performed for the current state of a socket".to_string(),
-        SrtError::BoundSock => "The socket is currently bound and the required operation cannot be performed in this state".to_string(),
-        SrtError::ConnSock => "The socket is currently connected and therefore performing the required operation is not possible".to_string(),
-        SrtError::InvParam => "Call parameters for API functions have some requirements that were not satisfied".to_string(),
-        SrtError::InvSock => "The API function required an ID of an entity (socket or group) and it was invalid".to_string(),
-        SrtError::UnboundSock => "The operation to be performed on a socket requires that it first be explicitly bound".to_string(),
-        SrtError::NoListen => "The socket passed for the operation is required to be in the listen state".to_string(),
-        SrtError::RdvNoServ => "The required operation cannot be performed when the socket is set to rendezvous mode".to_string(),
-        SrtError::RdvUnbound => "An attempt was made to connect to a socket set to rendezvous mode that was not first bound".to_string(),
-        SrtError::InvalMsgApi => "The function was used incorrectly in the message API".to_string(),
-        SrtError::InvalBufferApi => "The function was used incorrectly in the stream (buffer) API".to_string(),
-        SrtError::DupListen => "The port tried to be bound for listening is already busy".to_string(),
-        SrtError::LargeMsg => "Size exceeded".to_string(),
-        SrtError::InvPollId => "The epoll ID passed to an epoll function is invalid".to_string(),
-        SrtError::PollEmpty => "The epoll container currently has no subscribed sockets".to_string(),
-        SrtError::AsyncFail => "General asynchronous failure (not in use currently)".to_string(),
-        SrtError::AsyncSnd => "Sending operation is not ready to perform".to_string(),
-        SrtError::AsyncRcv => "Receiving operation is not ready to perform".to_string(),
-        SrtError::Timeout => "The operation timed out".to_string(),
-        SrtError::Congest => "With SRTO_TSBPDMODE and SRTO_TLPKTDROP set to true, some packets were dropped by sender".to_string(),
-        SrtError::PeerErr => "Receiver peer is writing to a file that the agent is sending".to_string(),
+        SrtErrorKind::Unknown => "Internal error when setting the right error code".to_string(),
+        SrtErrorKind::Success => "The value set when the last error was cleared and no error has occurred since then".to_string(),
+        SrtErrorKind::ConnSetup => "General setup error resulting from internal system state".to_string(),
+        SrtErrorKind::NoServer => "Connection timed out while attempting to connect to the remote address".to_string(),
+        SrtErrorKind::ConnRej(reason) => format!("Connection has been rejected: {:?}", reason),
+        SrtErrorKind::SockFail => "An error occurred when trying to call a system function on an internally used UDP socket".to_string(),
+        SrtErrorKind::SecFail => "A possible tampering with the handshake packets was detected, or encryption request wasn't properly fulfilled.".to_string(),
+        SrtErrorKind::Closed => "A socket that was vital for an operation called in blocking mode has been closed during the operation".to_string(),
+        SrtErrorKind::ConnFail => "General connection failure of unknown details".to_string(),
+        SrtErrorKind::ConnLost => "The socket was properly connected, but the connection has been broken".to_string(),
+        SrtErrorKind::NoConn => "The socket is not connected".to_string(),
+        SrtErrorKind::Resource => "System or standard library error reported unexpectedly for unknown purpose".to_string(),
+        SrtErrorKind::Thread => "System was unable to spawn a new thread when requried".to_string(),
+        SrtErrorKind::NoBuf => "System was unable to allocate memory for buffers".to_string(),
+        SrtErrorKind::SysObj => "System was unable to allocate system specific objects".to_string(),
+        SrtErrorKind::File => "General filesystem error (for functions operating with file transmission)".to_string(),
+        SrtErrorKind::InvRdOff => "Failure when trying to read from a given position in the file".to_string(),
+        SrtErrorKind::RdPerm => "Read permission was denied when trying to read from file".to_string(),
+        SrtErrorKind::InvWrOff => "Failed to set position in the written file".to_string(),
+        SrtErrorKind::WrPerm => "Write permission was denied when trying to write to a file".to_string(),
+        SrtErrorKind::InvOp => "Invalid operation performed for the current state of a socket".to_string(),
+        SrtErrorKind::BoundSock => "The socket is currently bound and the required operation cannot be performed in this state".to_string(),
+        SrtErrorKind::ConnSock => "The socket is currently connected and therefore performing the required operation is not possible".to_string(),
+        SrtErrorKind::InvParam => "Call parameters for API functions have some requirements that were not satisfied".to_string(),
+        SrtErrorKind::InvSock => "The API function required an ID of an entity (socket or group) and it was invalid".to_string(),
+        SrtErrorKind::UnboundSock => "The operation to be performed on a socket requires that it first be explicitly bound".to_string(),
+        SrtErrorKind::NoListen => "The socket passed for the operation is required to be in the listen state".to_string(),
+        SrtErrorKind::RdvNoServ => "The required operation cannot be performed when the socket is set to rendezvous mode".to_string(),
+        SrtErrorKind::RdvUnbound => "An attempt was made to connect to a socket set to rendezvous mode that was not first bound".to_string(),
+        SrtErrorKind::InvalMsgApi => "The function was used incorrectly in the message API".to_string(),
+        SrtErrorKind::InvalBufferApi => "The function was used incorrectly in the stream (buffer) API".to_string(),
+        SrtErrorKind::DupListen => "The port tried to be bound for listening is already busy".to_string(),
+        SrtErrorKind::LargeMsg => "Size exceeded".to_string(),
+        SrtErrorKind::InvPollId => "The epoll ID passed to an epoll function is invalid".to_string(),
+        SrtErrorKind::PollEmpty => "The epoll container currently has no subscribed sockets".to_string(),
+        SrtErrorKind::AsyncFail => "General asynchronous failure (not in use currently)".to_string(),
+        SrtErrorKind::AsyncSnd => "Sending operation is not ready to perform".to_string(),
+        SrtErrorKind::AsyncRcv => "Receiving operation is not ready to perform".to_string(),
+        SrtErrorKind::Timeout => "The operation timed out".to_string(),
+        SrtErrorKind::Congest => "With SRTO_TSBPDMODE and SRTO_TLPKTDROP set to true, some packets were dropped by sender".to_string(),
+        SrtErrorKind::PeerErr => "Receiver peer is writing to a file that the agent is sending".to_string(),
+        SrtErrorKind::Other(code) => format!("Unknown libsrt error {}", code),
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SrtRejectReason {
     Unknown,    // initial set when in progress
     System,     // broken due to system function error
@@ -241,6 +339,10 @@ pub enum SrtRejectReason {
     Filter,     // incompatible packet filter
     Group,      // incompatible group
     Timeout,    // connection timeout
+    Crypto,     // conflicting cryptographic configurations
+    /// Application codes: `1000 + HTTP status` for predefined ones, 2000 and
+    /// up for user defined ones.
+    Other(u32),
 }
 
 impl From<srt::SRT_REJECT_REASON> for SrtRejectReason {
@@ -263,7 +365,8 @@ impl From<srt::SRT_REJECT_REASON> for SrtRejectReason {
             srt::SRT_REJECT_REASON::SRT_REJ_FILTER => SrtRejectReason::Filter,
             srt::SRT_REJECT_REASON::SRT_REJ_GROUP => SrtRejectReason::Group,
             srt::SRT_REJECT_REASON::SRT_REJ_TIMEOUT => SrtRejectReason::Timeout,
-            _ => unreachable!("unrecognized SRT_REJECT_REASON"),
+            srt::SRT_REJECT_REASON::SRT_REJ_CRYPTO => SrtRejectReason::Crypto,
+            srt::SRT_REJECT_REASON(code) => SrtRejectReason::Other(code),
         }
     }
 }
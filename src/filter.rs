@@ -0,0 +1,206 @@
+use crate::{error::SrtError, socket::SrtSocket};
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+/// Value of `SRTO_PACKETFILTER`, e.g. `fec,cols:10,rows:5,layout:staircase,arq:onreq`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PacketFilter {
+    Fec(FecConfig),
+}
+
+/// Built-in forward error correction. Options left unset are agreed with
+/// the peer during the handshake.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FecConfig {
+    cols: i32,
+    rows: Option<i32>,
+    layout: Option<FecLayout>,
+    arq: Option<FecArq>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FecLayout {
+    Even,
+    Staircase,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FecArq {
+    Always,
+    OnReq,
+    Never,
+}
+
+impl FecConfig {
+    pub fn new(cols: i32) -> std::result::Result<Self, PacketFilterError> {
+        if cols < 1 {
+            return Err(PacketFilterError::Columns(cols));
+        }
+        Ok(Self {
+            cols,
+            rows: None,
+            layout: None,
+            arq: None,
+        })
+    }
+    /// A negative row count keeps the column groups of that size but turns
+    /// row FEC off.
+    pub fn set_rows(mut self, rows: i32) -> std::result::Result<Self, PacketFilterError> {
+        if rows == 0 {
+            return Err(PacketFilterError::Rows(rows));
+        }
+        self.rows = Some(rows);
+        Ok(self)
+    }
+    pub fn set_layout(mut self, layout: FecLayout) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+    pub fn set_arq(mut self, arq: FecArq) -> Self {
+        self.arq = Some(arq);
+        self
+    }
+    pub fn cols(&self) -> i32 {
+        self.cols
+    }
+    pub fn rows(&self) -> Option<i32> {
+        self.rows
+    }
+    pub fn layout(&self) -> Option<FecLayout> {
+        self.layout
+    }
+    pub fn arq(&self) -> Option<FecArq> {
+        self.arq
+    }
+}
+
+impl Display for PacketFilter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PacketFilter::Fec(fec) => {
+                write!(f, "fec,cols:{}", fec.cols)?;
+                if let Some(rows) = fec.rows {
+                    write!(f, ",rows:{}", rows)?;
+                }
+                if let Some(layout) = fec.layout {
+                    let layout = match layout {
+                        FecLayout::Even => "even",
+                        FecLayout::Staircase => "staircase",
+                    };
+                    write!(f, ",layout:{}", layout)?;
+                }
+                if let Some(arq) = fec.arq {
+                    let arq = match arq {
+                        FecArq::Always => "always",
+                        FecArq::OnReq => "onreq",
+                        FecArq::Never => "never",
+                    };
+                    write!(f, ",arq:{}", arq)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl FromStr for PacketFilter {
+    type Err = PacketFilterError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || PacketFilterError::Parse(s.to_string());
+        let mut parts = s.split(',');
+        if parts.next() != Some("fec") {
+            return Err(invalid());
+        }
+        let mut cols = None;
+        let mut rows = None;
+        let mut layout = None;
+        let mut arq = None;
+        for part in parts {
+            let mut key_value = part.splitn(2, ':');
+            let (key, value) = match (key_value.next(), key_value.next()) {
+                (Some(key), Some(value)) => (key, value),
+                _ => return Err(invalid()),
+            };
+            match key {
+                "cols" => cols = Some(value.parse().map_err(|_| invalid())?),
+                "rows" => rows = Some(value.parse().map_err(|_| invalid())?),
+                "layout" => {
+                    layout = Some(match value {
+                        "even" => FecLayout::Even,
+                        "staircase" => FecLayout::Staircase,
+                        _ => return Err(invalid()),
+                    })
+                }
+                "arq" => {
+                    arq = Some(match value {
+                        "always" => FecArq::Always,
+                        "onreq" => FecArq::OnReq,
+                        "never" => FecArq::Never,
+                        _ => return Err(invalid()),
+                    })
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        let mut fec = FecConfig::new(cols.ok_or_else(invalid)?)?;
+        if let Some(rows) = rows {
+            fec = fec.set_rows(rows)?;
+        }
+        fec.layout = layout;
+        fec.arq = arq;
+        Ok(PacketFilter::Fec(fec))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PacketFilterError {
+    Columns(i32),
+    Rows(i32),
+    Parse(String),
+}
+
+impl Display for PacketFilterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PacketFilterError::Columns(cols) => {
+                write!(f, "FEC needs at least 1 column, got {}", cols)
+            }
+            PacketFilterError::Rows(rows) => write!(f, "FEC rows must not be {}", rows),
+            PacketFilterError::Parse(s) => write!(f, "unrecognized packet filter {:?}", s),
+        }
+    }
+}
+
+impl Error for PacketFilterError {}
+
+/// Packet filter counters since the connection started.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FilterStats {
+    /// Control packets sent by the filter, i.e. FEC packets.
+    pub sent_extra: i32,
+    /// Control packets received by the filter.
+    pub received_extra: i32,
+    /// Lost packets the filter rebuilt.
+    pub supplied: i32,
+    /// Lost packets the filter could not rebuild.
+    pub lost: i32,
+}
+
+impl FilterStats {
+    pub fn of(socket: &SrtSocket) -> Result<Self> {
+        let stats = socket.bistats_total()?;
+        Ok(Self {
+            sent_extra: stats.pktSndFilterExtraTotal,
+            received_extra: stats.pktRcvFilterExtraTotal,
+            supplied: stats.pktRcvFilterSupplyTotal,
+            lost: stats.pktRcvFilterLossTotal,
+        })
+    }
+}
@@ -0,0 +1,124 @@
+//! Relays live-mode messages from one SRT connection to another, keeping the
+//! time each entered the first hop.
+//!
+//! Every hop otherwise restarts the clock: its receiver delivers a message
+//! its latency after the relay sent it, so the delay along a chain of relays
+//! is unknown at the far end. Forwarding the source time instead makes the
+//! last receiver deliver at a fixed delay from the original sender.
+
+use crate::{error::SrtErrorKind, time_now, SrtStream};
+
+use std::{
+    io,
+    num::NonZeroI64,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+const MAX_MESSAGE_SIZE: usize = 1500;
+
+/// One forwarded message.
+#[derive(Clone, Copy, Debug)]
+pub struct Forwarded {
+    pub msg_no: i32,
+    pub len: usize,
+    /// Time since the message entered the first hop, or `None` if it came
+    /// without a source time.
+    pub delay: Option<Duration>,
+    /// Sent with the current time because its source time predates the
+    /// output connection, which libsrt refuses.
+    pub restamped: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ForwardStats {
+    messages: AtomicU64,
+    bytes: AtomicU64,
+    restamped: AtomicU64,
+    max_delay_us: AtomicU64,
+}
+
+impl ForwardStats {
+    pub fn messages(&self) -> u64 {
+        self.messages.load(Ordering::Relaxed)
+    }
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+    /// Messages that lost their source time, see `Forwarded::restamped`.
+    pub fn restamped(&self) -> u64 {
+        self.restamped.load(Ordering::Relaxed)
+    }
+    /// Largest `Forwarded::delay` so far.
+    pub fn max_delay(&self) -> Duration {
+        Duration::from_micros(self.max_delay_us.load(Ordering::Relaxed))
+    }
+    fn add(counter: &AtomicU64, n: u64) {
+        counter.fetch_add(n, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Forwarder {
+    stats: Arc<ForwardStats>,
+}
+
+impl Forwarder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn stats(&self) -> Arc<ForwardStats> {
+        self.stats.clone()
+    }
+    /// Forwards until `input` is closed or either side fails, calling `report`
+    /// for every message. `output` needs the message API and timestamp
+    /// based delivery, as in live mode; otherwise libsrt ignores source
+    /// times. Its latency then has to cover the earlier hops too, or the
+    /// receiver drops messages as too late.
+    pub fn run<F>(&self, input: &SrtStream, output: &SrtStream, mut report: F) -> io::Result<()>
+    where
+        F: FnMut(&Forwarded),
+    {
+        let connected_at = output.connection_time()?;
+        let mut buf = vec![0; MAX_MESSAGE_SIZE];
+        loop {
+            let (len, ctrl) = match input.recvmsg2(&mut buf) {
+                Ok(received) => received,
+                Err(e) if matches!(e.kind(), SrtErrorKind::ConnLost | SrtErrorKind::NoConn) => {
+                    return Ok(())
+                }
+                Err(e) => return Err(e.into()),
+            };
+            let src_time = ctrl.src_time.filter(|t| t.get() >= connected_at);
+            let restamped = src_time.is_none() && ctrl.src_time.is_some();
+            output.sendmsg2(&buf[..len], src_time)?;
+            let forwarded = Forwarded {
+                msg_no: ctrl.msg_no,
+                len,
+                delay: ctrl.src_time.map(delay_since),
+                restamped,
+            };
+            self.record(&forwarded);
+            report(&forwarded);
+        }
+    }
+    fn record(&self, forwarded: &Forwarded) {
+        ForwardStats::add(&self.stats.messages, 1);
+        ForwardStats::add(&self.stats.bytes, forwarded.len as u64);
+        if forwarded.restamped {
+            ForwardStats::add(&self.stats.restamped, 1);
+        }
+        if let Some(delay) = forwarded.delay {
+            self.stats
+                .max_delay_us
+                .fetch_max(delay.as_micros() as u64, Ordering::Relaxed);
+        }
+    }
+}
+
+fn delay_since(src_time: NonZeroI64) -> Duration {
+    Duration::from_micros((time_now() - src_time.get()).max(0) as u64)
+}
@@ -0,0 +1,331 @@
+//! Bridges plain UDP, typically MPEG-TS, and live-mode SRT.
+//!
+//! `UdpToSrt` packs received datagrams into SRT payloads of up to
+//! `GatewayConfig::max_payload` bytes; `SrtToUdp` sends every SRT payload it
+//! receives as one datagram.
+
+use crate::{SrtAsyncStream, SrtStream};
+
+use futures::{
+    channel::mpsc,
+    executor,
+    io::{AsyncReadExt, AsyncWriteExt},
+    stream::StreamExt,
+};
+
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    mem,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+/// Largest payload a live-mode SRT message carries, seven MPEG-TS packets.
+pub const LIVE_PAYLOAD_SIZE: usize = 1316;
+const MAX_DATAGRAM_SIZE: usize = 65536;
+
+type Payloads = mpsc::Receiver<Vec<u8>>;
+
+#[derive(Clone, Copy, Debug)]
+pub struct GatewayConfig {
+    /// Datagrams are packed into payloads of at most this many bytes.
+    pub max_payload: usize,
+    /// A partly filled payload is sent once it is this old.
+    pub flush_after: Duration,
+    /// Payloads waiting for the SRT side; more are dropped as overruns.
+    pub queue_len: usize,
+}
+
+impl Default for GatewayConfig {
+    fn default() -> Self {
+        Self {
+            max_payload: LIVE_PAYLOAD_SIZE,
+            flush_after: Duration::from_millis(10),
+            queue_len: 64,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct GatewayStats {
+    datagrams: AtomicU64,
+    payloads: AtomicU64,
+    bytes: AtomicU64,
+    filtered: AtomicU64,
+    oversized: AtomicU64,
+    overruns: AtomicU64,
+}
+
+impl GatewayStats {
+    pub fn datagrams(&self) -> u64 {
+        self.datagrams.load(Ordering::Relaxed)
+    }
+    pub fn payloads(&self) -> u64 {
+        self.payloads.load(Ordering::Relaxed)
+    }
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+    /// Datagrams dropped by the source filter.
+    pub fn filtered(&self) -> u64 {
+        self.filtered.load(Ordering::Relaxed)
+    }
+    /// Datagrams dropped for not fitting in one payload.
+    pub fn oversized(&self) -> u64 {
+        self.oversized.load(Ordering::Relaxed)
+    }
+    /// Payloads dropped because the output could not keep up.
+    pub fn overruns(&self) -> u64 {
+        self.overruns.load(Ordering::Relaxed)
+    }
+    fn add(counter: &AtomicU64, n: u64) {
+        counter.fetch_add(n, Ordering::Relaxed);
+    }
+}
+
+pub struct UdpReceiver {
+    socket: UdpSocket,
+    sources: Vec<IpAddr>,
+}
+
+impl UdpReceiver {
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
+        Ok(Self {
+            socket: UdpSocket::bind(addr)?,
+            sources: Vec::new(),
+        })
+    }
+    /// Binds `group`'s port and joins the group on `interface`, or on the
+    /// default interface if `None`. IPv6 groups are only joined on the
+    /// default interface; giving an `interface` for one is `InvalidInput`.
+    pub fn join_multicast(group: SocketAddr, interface: Option<IpAddr>) -> io::Result<Self> {
+        let socket = match (group.ip(), interface) {
+            (IpAddr::V4(ip), interface) => {
+                let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, group.port()))?;
+                let interface = match interface {
+                    Some(IpAddr::V4(interface)) => interface,
+                    Some(IpAddr::V6(_)) => return Err(ErrorKind::InvalidInput.into()),
+                    None => Ipv4Addr::UNSPECIFIED,
+                };
+                socket.join_multicast_v4(&ip, &interface)?;
+                socket
+            }
+            (IpAddr::V6(_), Some(_)) => return Err(ErrorKind::InvalidInput.into()),
+            (IpAddr::V6(ip), None) => {
+                let socket = UdpSocket::bind((Ipv6Addr::UNSPECIFIED, group.port()))?;
+                socket.join_multicast_v6(&ip, 0)?;
+                socket
+            }
+        };
+        Ok(Self {
+            socket,
+            sources: Vec::new(),
+        })
+    }
+    /// Only accepts datagrams sent from `source`; may be called repeatedly.
+    /// Filtering happens after reception, it does not change the group
+    /// membership.
+    pub fn allow_source(mut self, source: IpAddr) -> Self {
+        self.sources.push(source);
+        self
+    }
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+    fn is_allowed(&self, from: &SocketAddr) -> bool {
+        self.sources.is_empty() || self.sources.contains(&from.ip())
+    }
+}
+
+pub struct UdpToSrt {
+    receiver: UdpReceiver,
+    config: GatewayConfig,
+    stats: Arc<GatewayStats>,
+}
+
+impl UdpToSrt {
+    pub fn new(receiver: UdpReceiver, config: GatewayConfig) -> Self {
+        Self {
+            receiver,
+            config,
+            stats: Arc::new(GatewayStats::default()),
+        }
+    }
+    pub fn stats(&self) -> Arc<GatewayStats> {
+        self.stats.clone()
+    }
+    /// Forwards until writing to `output` or receiving UDP fails, returning
+    /// that error.
+    pub fn run(self, output: &mut SrtStream) -> io::Result<()> {
+        let stats = self.stats.clone();
+        let (payloads, reader) = self.spawn_reader()?;
+        for payload in executor::block_on_stream(payloads) {
+            output.write_all(&payload)?;
+            GatewayStats::add(&stats.payloads, 1);
+            GatewayStats::add(&stats.bytes, payload.len() as u64);
+        }
+        reader_error(reader)
+    }
+    pub async fn run_async(self, output: &mut SrtAsyncStream) -> io::Result<()> {
+        let stats = self.stats.clone();
+        let (mut payloads, reader) = self.spawn_reader()?;
+        while let Some(payload) = payloads.next().await {
+            output.write_all(&payload).await?;
+            GatewayStats::add(&stats.payloads, 1);
+            GatewayStats::add(&stats.bytes, payload.len() as u64);
+        }
+        reader_error(reader)
+    }
+    // UDP is read on its own thread so a stalled SRT side shows up as
+    // overruns instead of as kernel buffer drops nobody can count. The thread
+    // ends once the returned receiver is dropped, or with the error that
+    // stopped it.
+    fn spawn_reader(self) -> io::Result<(Payloads, JoinHandle<io::Result<()>>)> {
+        let Self {
+            receiver,
+            config,
+            stats,
+        } = self;
+        receiver.socket.set_read_timeout(Some(config.flush_after))?;
+        let (mut tx, rx) = mpsc::channel(config.queue_len);
+        let reader = thread::spawn(move || {
+            let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+            let mut pending = Vec::with_capacity(config.max_payload);
+            let mut pending_since = Instant::now();
+            while !tx.is_closed() {
+                match receiver.socket.recv_from(&mut buf) {
+                    Ok((len, from)) => {
+                        if !receiver.is_allowed(&from) {
+                            GatewayStats::add(&stats.filtered, 1);
+                            continue;
+                        }
+                        GatewayStats::add(&stats.datagrams, 1);
+                        if len > config.max_payload {
+                            GatewayStats::add(&stats.oversized, 1);
+                            continue;
+                        }
+                        if pending.len() + len > config.max_payload {
+                            flush(&mut tx, &mut pending, &config, &stats);
+                        }
+                        if pending.is_empty() {
+                            pending_since = Instant::now();
+                        }
+                        pending.extend_from_slice(&buf[..len]);
+                        if pending.len() == config.max_payload
+                            || pending_since.elapsed() >= config.flush_after
+                        {
+                            flush(&mut tx, &mut pending, &config, &stats);
+                        }
+                    }
+                    Err(ref e)
+                        if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                    {
+                        flush(&mut tx, &mut pending, &config, &stats);
+                    }
+                    Err(e) => {
+                        flush(&mut tx, &mut pending, &config, &stats);
+                        return Err(e);
+                    }
+                }
+            }
+            Ok(())
+        });
+        Ok((rx, reader))
+    }
+}
+
+// The payload channel only closes once the reader has stopped.
+fn reader_error(reader: JoinHandle<io::Result<()>>) -> io::Result<()> {
+    match reader.join() {
+        Ok(Err(e)) => Err(e),
+        _ => Err(ErrorKind::BrokenPipe.into()),
+    }
+}
+
+fn flush(
+    tx: &mut mpsc::Sender<Vec<u8>>,
+    pending: &mut Vec<u8>,
+    config: &GatewayConfig,
+    stats: &GatewayStats,
+) {
+    if pending.is_empty() {
+        return;
+    }
+    let payload = mem::replace(pending, Vec::with_capacity(config.max_payload));
+    if let Err(e) = tx.try_send(payload) {
+        if e.is_full() {
+            GatewayStats::add(&stats.overruns, 1);
+        }
+    }
+}
+
+pub struct SrtToUdp {
+    socket: UdpSocket,
+    destination: SocketAddr,
+    stats: Arc<GatewayStats>,
+}
+
+impl SrtToUdp {
+    pub fn new(destination: SocketAddr) -> io::Result<Self> {
+        let unspecified = match destination {
+            SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+            SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        };
+        Self::with_socket(UdpSocket::bind((unspecified, 0))?, destination)
+    }
+    /// Sends from an already configured socket, e.g. one with a multicast TTL.
+    pub fn with_socket(socket: UdpSocket, destination: SocketAddr) -> io::Result<Self> {
+        // Non-blocking so a full send buffer is counted as an overrun rather
+        // than stalling the SRT receiver.
+        socket.set_nonblocking(true)?;
+        Ok(Self {
+            socket,
+            destination,
+            stats: Arc::new(GatewayStats::default()),
+        })
+    }
+    pub fn stats(&self) -> Arc<GatewayStats> {
+        self.stats.clone()
+    }
+    /// Forwards until `input` ends or fails.
+    pub fn run(&self, input: &mut SrtStream) -> io::Result<()> {
+        let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+        loop {
+            let len = input.read(&mut buf)?;
+            if len == 0 {
+                return Ok(());
+            }
+            self.forward(&buf[..len])?;
+        }
+    }
+    pub async fn run_async(&self, input: &mut SrtAsyncStream) -> io::Result<()> {
+        let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+        loop {
+            let len = input.read(&mut buf).await?;
+            if len == 0 {
+                return Ok(());
+            }
+            self.forward(&buf[..len])?;
+        }
+    }
+    fn forward(&self, payload: &[u8]) -> io::Result<()> {
+        GatewayStats::add(&self.stats.payloads, 1);
+        match self.socket.send_to(payload, self.destination) {
+            Ok(len) => {
+                GatewayStats::add(&self.stats.datagrams, 1);
+                GatewayStats::add(&self.stats.bytes, len as u64);
+                Ok(())
+            }
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                GatewayStats::add(&self.stats.overruns, 1);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
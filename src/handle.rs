@@ -0,0 +1,124 @@
+use crate::{error::SrtError, EncryptionHealth, FilterStats, SrtSocket, SrtSocketStatus};
+
+use libsrt_sys as srt;
+
+use std::net::SocketAddr;
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+/// Statistics and option access to a socket owned elsewhere.
+///
+/// A handle does not keep the socket open: once the owner closes it, calls
+/// through the handle and all its clones fail with `SrtErrorKind::InvSock`,
+/// except `get_socket_state`, which reports the socket as closed.
+///
+/// # Thread safety
+///
+/// All socket types are `Send` and `Sync`; libsrt locks every socket
+/// internally. What may overlap on one socket:
+///
+/// - Reading and writing from different threads: libsrt keeps separate
+///   locks for the two directions, so `&SrtStream` implements `Read` and
+///   `Write` for a reader and a writer thread sharing one stream.
+/// - Two readers, or two writers: safe, but which call gets which message is
+///   unspecified.
+/// - Statistics and options, next to I/O: through an `SrtHandle`, which
+///   unlike `&SrtAsyncStream` stays usable while a task polls the stream.
+/// - Closing: needs ownership. Calls still running elsewhere, e.g. through a
+///   handle, then fail with `SrtErrorKind::InvSock`.
+///
+/// Futures only need `Send`; polling one from two tasks is not meaningful.
+#[derive(Clone, Debug)]
+pub struct SrtHandle {
+    socket: SrtSocket,
+}
+
+impl SrtHandle {
+    pub(crate) fn new(socket: SrtSocket) -> Self {
+        Self { socket }
+    }
+    pub fn local_addr(&self) -> Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+    pub fn peer_addr(&self) -> Result<SocketAddr> {
+        self.socket.peer_addr()
+    }
+    pub fn get_socket_state(&self) -> Result<SrtSocketStatus> {
+        self.socket.get_socket_state()
+    }
+    pub fn get_stream_id(&self) -> Result<String> {
+        self.socket.get_stream_id()
+    }
+    /// Totals since the connection was made; reading them resets nothing,
+    /// so any number of handles can poll them.
+    pub fn stats(&self) -> Result<srt::SRT_TRACEBSTATS> {
+        self.socket.bistats_total()
+    }
+    pub fn filter_stats(&self) -> Result<FilterStats> {
+        FilterStats::of(&self.socket)
+    }
+    pub fn encryption_health(&self) -> Result<EncryptionHealth> {
+        EncryptionHealth::of(&self.socket)
+    }
+    pub fn get_receive_data(&self) -> Result<i32> {
+        self.socket.get_receive_data()
+    }
+    pub fn get_send_data(&self) -> Result<i32> {
+        self.socket.get_send_data()
+    }
+    pub fn get_peer_latency(&self) -> Result<i32> {
+        self.socket.get_peer_latency()
+    }
+    pub fn get_receive_latency(&self) -> Result<i32> {
+        self.socket.get_receive_latency()
+    }
+    pub fn get_max_bandwith(&self) -> Result<i64> {
+        self.socket.get_max_bandwith()
+    }
+    pub fn set_max_bandwith(&self, bytes_per_sec: i64) -> Result<()> {
+        self.socket.set_max_bandwith(bytes_per_sec)
+    }
+    pub fn get_input_bandwith(&self) -> Result<i64> {
+        self.socket.get_input_bandwith()
+    }
+    pub fn set_input_bandwith(&self, bytes_per_sec: i64) -> Result<()> {
+        self.socket.set_input_bandwith(bytes_per_sec)
+    }
+    pub fn set_recovery_bandwidth_overhead(&self, per_cent: i32) -> Result<()> {
+        self.socket.set_recovery_bandwidth_overhead(per_cent)
+    }
+}
+
+const fn assert_send_sync<T: Send + Sync>() {}
+const fn assert_send<T: Send>() {}
+
+// Breaks the build if a change takes one of the guarantees above away.
+const _: () = {
+    use crate::*;
+
+    assert_send_sync::<SrtSocket>();
+    assert_send_sync::<SrtHandle>();
+    assert_send_sync::<SrtError>();
+    assert_send_sync::<SrtBuilder>();
+    assert_send_sync::<SrtAsyncBuilder>();
+    assert_send_sync::<SrtStream>();
+    assert_send_sync::<SrtListener>();
+    assert_send_sync::<SrtBoundSocket>();
+    assert_send_sync::<SrtConnecting>();
+    assert_send_sync::<SrtMultiListener>();
+    assert_send_sync::<SrtAsyncStream>();
+    assert_send_sync::<SrtAsyncListener>();
+    assert_send_sync::<SrtBoundAsyncSocket>();
+    assert_send_sync::<SrtAsyncMultiListener>();
+    assert_send_sync::<ConnectEvent>();
+    assert_send_sync::<ConnectState>();
+    assert_send_sync::<EncryptionMonitor>();
+    assert_send_sync::<relay::Relay>();
+    assert_send::<ReconnectingStream>();
+    assert_send::<ConnectFuture>();
+    assert_send::<AcceptFuture>();
+    assert_send::<MultiAcceptFuture>();
+    assert_send::<ShutdownFuture>();
+    assert_send::<ListenerShutdownFuture>();
+    assert_send::<RecvMsg2<Vec<u8>>>();
+};
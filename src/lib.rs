@@ -1,7 +1,28 @@
+mod connect_callback;
+mod connecting;
+mod context;
+pub mod encryption;
 pub mod error;
+mod filter;
+pub mod forward;
+pub mod gateway;
+mod handle;
+mod listen_callback;
+mod multi_listener;
+mod passphrase;
+mod reconnect;
+pub mod relay;
+pub mod rendezvous;
+pub mod shutdown;
 mod socket;
+#[cfg(target_family = "unix")]
+pub mod systemd;
+#[cfg(feature = "testing")]
+pub mod testing;
+mod timer;
+pub mod transport;
 
-use error::SrtError;
+use error::{SrtError, SrtErrorKind};
 use libsrt_sys as srt;
 
 use futures::{
@@ -11,30 +32,42 @@ use futures::{
 };
 
 use std::{
-    convert::TryInto,
     io::{self, Read, Write},
     iter::Iterator,
-    net::{SocketAddr, ToSocketAddrs},
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    num::NonZeroI64,
     ops::Drop,
     os::raw::c_int,
     pin::Pin,
+    sync::Arc,
     thread,
+    time::{Duration, Instant},
 };
 
+pub use connect_callback::{ConnectEvent, ConnectState};
+pub use connecting::SrtConnecting;
+pub use context::SrtContext;
+pub use encryption::{EncryptionEvent, EncryptionHealth, EncryptionMonitor};
+pub use filter::{FecArq, FecConfig, FecLayout, FilterStats, PacketFilter, PacketFilterError};
+pub use handle::SrtHandle;
+pub use multi_listener::{
+    BindError, MultiAcceptFuture, SrtAsyncMultiListener, SrtMultiListener,
+};
+pub use passphrase::{Passphrase, PassphraseError};
+pub use reconnect::{ReconnectConfig, ReconnectEvent, ReconnectingStream, WritePolicy};
 pub use socket::{
-    SrtCongestionController, SrtKmState, SrtSocket, SrtSocketStatus, SrtTransmissionType,
+    SrtCongestionController, SrtCryptoMode, SrtKmState, SrtSocket, SrtSocketStatus,
+    SrtTransmissionType,
 };
 use crate::socket::RecvMsgCtrl;
 
 type Result<T> = std::result::Result<T, SrtError>;
 
+/// Explicitly starts libsrt. Sockets start the library on their own, so this
+/// is only needed to keep it running between sockets. Each call must be
+/// matched by one `cleanup()`; prefer holding an `SrtContext` instead.
 pub fn startup() -> Result<()> {
-    let result = unsafe { srt::srt_startup() };
-    if result == 1 {
-        Ok(())
-    } else {
-        error::handle_result((), result)
-    }
+    context::retain()
 }
 
 pub mod log {
@@ -63,9 +96,28 @@ pub mod log {
     }
 }
 
+/// libsrt's clock in microseconds, which source times are given in.
+pub fn time_now() -> i64 {
+    unsafe { srt::srt_time_now() }
+}
+
+/// Releases one `startup()`. libsrt is only cleaned up once no `SrtContext`
+/// or socket remains; calling this without a matching `startup()` fails with
+/// `SrtErrorKind::InvOp`.
 pub fn cleanup() -> Result<()> {
-    let result = unsafe { srt::srt_cleanup() };
-    error::handle_result((), result)
+    context::release()
+}
+
+/// Whether the linked libsrt was built with encryption support. Without it,
+/// setting a passphrase fails.
+pub fn encryption_available() -> bool {
+    let socket = match SrtSocket::new() {
+        Ok(socket) => socket,
+        Err(_) => return false,
+    };
+    let available = socket.set_passphrase("encryption-probe").is_ok();
+    let _ = socket.close();
+    available
 }
 
 pub fn builder() -> SrtBuilder {
@@ -91,9 +143,20 @@ impl SrtListener {
     pub fn close(self) -> Result<()> {
         self.socket.close()
     }
+    /// Stops accepting and waits until every connection accepted from this
+    /// listener has been closed or broken. Connections still open after
+    /// `timeout` are closed and `SrtErrorKind::Timeout` is returned.
+    pub fn shutdown(self, timeout: Option<Duration>) -> Result<()> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        self.socket.close()?;
+        shutdown::wait_accepted(self.socket, deadline)
+    }
     pub fn local_addr(&self) -> Result<SocketAddr> {
         self.socket.local_addr()
     }
+    pub fn handle(&self) -> SrtHandle {
+        SrtHandle::new(self.socket)
+    }
 }
 
 impl Drop for SrtListener {
@@ -110,12 +173,25 @@ impl SrtStream {
     pub fn local_addr(&self) -> Result<SocketAddr> {
         self.socket.local_addr()
     }
+    pub fn handle(&self) -> SrtHandle {
+        SrtHandle::new(self.socket)
+    }
     pub fn peer_addr(&self) -> Result<SocketAddr> {
         self.socket.peer_addr()
     }
     pub fn close(self) -> Result<()> {
         self.socket.close()
     }
+    /// Blocks until all sent data has been acknowledged by the peer, then
+    /// closes the stream. The stream is closed even if `timeout` expires
+    /// first, in which case `SrtErrorKind::Timeout` is returned.
+    pub fn shutdown(self, timeout: Option<Duration>) -> Result<()> {
+        let drained = self
+            .socket
+            .drain(timeout.map(|timeout| Instant::now() + timeout));
+        let closed = self.socket.close();
+        drained.and(closed)
+    }
     pub fn set_time_drift_tracer(&self, enable: bool) -> Result<()> {
         self.socket.set_time_drift_tracer(enable)
     }
@@ -170,6 +246,27 @@ impl SrtStream {
     pub fn get_nak_report(&self) -> Result<bool> {
         self.socket.get_nak_report()
     }
+    /// The filter agreed with the peer, if any.
+    pub fn get_packet_filter(&self) -> Result<Option<PacketFilter>> {
+        let filter = self.socket.get_packet_filter()?;
+        if filter.is_empty() {
+            return Ok(None);
+        }
+        filter
+            .parse()
+            .map(Some)
+            .map_err(|_| SrtErrorKind::InvParam.into())
+    }
+    pub fn filter_stats(&self) -> Result<FilterStats> {
+        FilterStats::of(&self.socket)
+    }
+    /// Connection statistics; the interval counters restart on every call.
+    pub fn bistats(&self) -> Result<srt::SRT_TRACEBSTATS> {
+        self.socket.bistats()
+    }
+    pub fn get_crypto_mode(&self) -> Result<SrtCryptoMode> {
+        self.socket.get_crypto_mode()
+    }
     pub fn get_encryption_key_length(&self) -> Result<i32> {
         self.socket.get_encryption_key_length()
     }
@@ -212,6 +309,12 @@ impl SrtStream {
     pub fn get_send_km_state(&self) -> Result<SrtKmState> {
         self.socket.get_send_km_state()
     }
+    pub fn encryption_health(&self) -> Result<EncryptionHealth> {
+        EncryptionHealth::of(&self.socket)
+    }
+    pub fn encryption_monitor(&self) -> EncryptionMonitor {
+        EncryptionMonitor::new(self.socket)
+    }
     pub fn get_send_blocking(&self) -> Result<bool> {
         self.socket.get_send_blocking()
     }
@@ -239,6 +342,21 @@ impl SrtStream {
     pub fn get_srt_version(&self) -> Result<i32> {
         self.socket.get_srt_version()
     }
+    /// Reads one message along with its source time, see `sendmsg2`.
+    pub fn recvmsg2(&self, buf: &mut [u8]) -> Result<(usize, RecvMsgCtrl)> {
+        self.socket.recvmsg2(buf)
+    }
+    /// Sends one message stamped with `src_time`, on the `time_now()` clock,
+    /// instead of the current time, so that the receiver delivers it that
+    /// much later after it entered the first hop. Needs the message API and
+    /// timestamp based delivery, as in live mode.
+    pub fn sendmsg2(&self, buf: &[u8], src_time: Option<NonZeroI64>) -> Result<usize> {
+        self.socket.sendmsg2(buf, src_time)
+    }
+    /// When the connection was made, on the `time_now()` clock.
+    pub fn connection_time(&self) -> Result<i64> {
+        self.socket.connection_time()
+    }
 }
 
 impl Read for SrtStream {
@@ -256,6 +374,22 @@ impl Write for SrtStream {
     }
 }
 
+/// For a reader and a writer thread sharing the stream, see `SrtHandle`.
+impl Read for &SrtStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        Ok(self.socket.recv(buf)?)
+    }
+}
+
+impl Write for &SrtStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(self.socket.send(buf)?)
+    }
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
 impl Drop for SrtStream {
     fn drop(&mut self) {
         if let Err(_) = self.socket.close() {}
@@ -273,6 +407,18 @@ impl SrtBoundSocket {
             socket: self.socket,
         })
     }
+    /// Like `connect`, but returns at once.
+    pub fn start_connect<A: ToSocketAddrs>(self, remote: A) -> Result<SrtConnecting> {
+        let connecting = SrtConnecting::watch(self.socket)?;
+        self.socket.connect(remote)?;
+        Ok(connecting)
+    }
+    pub fn listen(self, backlog: i32) -> Result<SrtListener> {
+        self.socket.listen(backlog)?;
+        Ok(SrtListener {
+            socket: self.socket,
+        })
+    }
     pub fn local_addr(&self) -> Result<SocketAddr> {
         self.socket.local_addr()
     }
@@ -289,12 +435,40 @@ impl SrtBuilder {
         let socket = socket.bind(local)?;
         Ok(SrtBoundSocket { socket })
     }
+    /// Like `bind`, but uses `udp` instead of creating a UDP socket. libsrt
+    /// takes ownership of `udp`.
+    pub fn bind_acquire(self, udp: UdpSocket) -> Result<SrtBoundSocket> {
+        let socket = SrtSocket::new()?;
+        self.config_socket(&socket)?;
+        let socket = socket.bind_acquire(udp)?;
+        Ok(SrtBoundSocket { socket })
+    }
     pub fn connect<A: ToSocketAddrs>(self, remote: A) -> Result<SrtStream> {
         let socket = SrtSocket::new()?;
         self.config_socket(&socket)?;
         socket.connect(remote)?;
         Ok(SrtStream { socket })
     }
+    /// Calls `callback` with the outcome of every failed attempt made with
+    /// `start_connect`, including its reject reason; `connect` blocks and
+    /// returns the failure instead. Socket groups, whose members libsrt
+    /// also reports, are not supported by this crate.
+    pub fn set_connect_callback(
+        mut self,
+        callback: impl Fn(&ConnectEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.opt_vec
+            .push(SrtPreConnectOpt::ConnectCallback(Arc::new(callback)));
+        self
+    }
+    /// Like `connect`, but returns at once.
+    pub fn start_connect<A: ToSocketAddrs>(self, remote: A) -> Result<SrtConnecting> {
+        let socket = SrtSocket::new()?;
+        self.config_socket(&socket)?;
+        let connecting = SrtConnecting::watch(socket)?;
+        socket.connect(remote)?;
+        Ok(connecting)
+    }
     pub fn listen<A: ToSocketAddrs>(self, addr: A, backlog: i32) -> Result<SrtListener> {
         let socket = SrtSocket::new()?;
         self.config_socket(&socket)?;
@@ -302,6 +476,41 @@ impl SrtBuilder {
         socket.listen(backlog)?;
         Ok(SrtListener { socket })
     }
+    /// Listens on every address `addrs` resolves to instead of only the
+    /// first one. Fails, closing everything, unless all of them can be bound.
+    pub fn listen_all<A: ToSocketAddrs>(
+        self,
+        addrs: A,
+        backlog: i32,
+    ) -> std::result::Result<SrtMultiListener, BindError> {
+        let listeners = multi_listener::listen_all(addrs, |addr| {
+            SrtBuilder {
+                opt_vec: self.opt_vec.clone(),
+            }
+            .listen(addr, backlog)
+        })?;
+        Ok(SrtMultiListener::new(listeners))
+    }
+    /// Listens on `port` of both the IPv4 and the IPv6 wildcard address,
+    /// with the IPv6 socket set to IPv6 only.
+    pub fn listen_dual_stack(
+        self,
+        port: u16,
+        backlog: i32,
+    ) -> std::result::Result<SrtMultiListener, BindError> {
+        let builder = self.set_ipv6_only(1);
+        let listeners = multi_listener::listen_dual_stack(
+            port,
+            |addr| {
+                SrtBuilder {
+                    opt_vec: builder.opt_vec.clone(),
+                }
+                .listen(addr, backlog)
+            },
+            SrtListener::local_addr,
+        )?;
+        Ok(SrtMultiListener::new(listeners))
+    }
     pub fn rendezvous<A: ToSocketAddrs>(self, local: A, remote: A) -> Result<SrtStream> {
         let socket = SrtSocket::new()?;
         socket.set_rendezvous(true)?;
@@ -309,6 +518,19 @@ impl SrtBuilder {
         socket.rendezvous(local, remote)?;
         Ok(SrtStream { socket })
     }
+    /// Like `rendezvous`, but returns at once.
+    pub fn start_rendezvous<A: ToSocketAddrs>(
+        self,
+        local: A,
+        remote: A,
+    ) -> Result<SrtConnecting> {
+        let socket = SrtSocket::new()?;
+        socket.set_rendezvous(true)?;
+        self.config_socket(&socket)?;
+        let connecting = SrtConnecting::watch(socket)?;
+        socket.rendezvous(local, remote)?;
+        Ok(connecting)
+    }
 }
 
 impl SrtBuilder {
@@ -374,11 +596,11 @@ impl SrtBuilder {
         self.opt_vec.push(SrtPreConnectOpt::NakReport(enable));
         self
     }
-    pub fn set_packet_filter(mut self, filter: String) -> Self {
+    pub fn set_packet_filter(mut self, filter: PacketFilter) -> Self {
         self.opt_vec.push(SrtPreConnectOpt::PacketFilter(filter));
         self
     }
-    pub fn set_passphrase(mut self, passphrase: String) -> Self {
+    pub fn set_passphrase(mut self, passphrase: Passphrase) -> Self {
         self.opt_vec.push(SrtPreConnectOpt::Passphrase(passphrase));
         self
     }
@@ -386,6 +608,10 @@ impl SrtBuilder {
         self.opt_vec.push(SrtPreConnectOpt::PayloadSize(bytes));
         self
     }
+    pub fn set_crypto_mode(mut self, mode: SrtCryptoMode) -> Self {
+        self.opt_vec.push(SrtPreConnectOpt::CryptoMode(mode));
+        self
+    }
     pub fn set_encryption_key_length(mut self, bytes: i32) -> Self {
         self.opt_vec.push(SrtPreConnectOpt::PBKeyLen(bytes));
         self
@@ -474,11 +700,20 @@ impl SrtBuilder {
         self
     }
     fn config_socket(self, socket: &SrtSocket) -> Result<()> {
+        // A half configured socket is of no use to the caller.
+        self.apply_opts(socket).inspect_err(|_| {
+            let _ = socket.close();
+        })
+    }
+    fn apply_opts(self, socket: &SrtSocket) -> Result<()> {
         for opt in self.opt_vec {
             match opt {
                 #[cfg(target_family = "unix")]
                 SrtPreConnectOpt::BindToDevice(value) => socket.set_bind_to_device(value)?,
                 SrtPreConnectOpt::ConnTimeO(value) => socket.set_connection_timeout(value)?,
+                SrtPreConnectOpt::ConnectCallback(callback) => {
+                    connect_callback::add(socket, callback)?
+                }
                 SrtPreConnectOpt::FC(value) => socket.set_flight_flag_size(value)?,
                 SrtPreConnectOpt::IpTos(value) => socket.set_ip_type_of_service(value)?,
                 SrtPreConnectOpt::IpTtl(value) => socket.set_ipv4_time_to_live(value)?,
@@ -492,9 +727,12 @@ impl SrtBuilder {
                 SrtPreConnectOpt::MinVersion(value) => socket.set_min_version(value)?,
                 SrtPreConnectOpt::Mss(value) => socket.set_mss(value)?,
                 SrtPreConnectOpt::NakReport(value) => socket.set_nak_report(value)?,
-                SrtPreConnectOpt::PacketFilter(value) => socket.set_packet_filter(&value)?,
-                SrtPreConnectOpt::Passphrase(value) => socket.set_passphrase(&value)?,
+                SrtPreConnectOpt::PacketFilter(value) => {
+                    socket.set_packet_filter(&value.to_string())?
+                }
+                SrtPreConnectOpt::Passphrase(value) => socket.set_passphrase(value.as_str())?,
                 SrtPreConnectOpt::PayloadSize(value) => socket.set_payload_size(value)?,
+                SrtPreConnectOpt::CryptoMode(value) => socket.set_crypto_mode(value)?,
                 SrtPreConnectOpt::PBKeyLen(value) => socket.set_encryption_key_length(value)?,
                 SrtPreConnectOpt::PeerIdleTimeO(value) => socket.set_peer_idle_timeout(value)?,
                 SrtPreConnectOpt::PeerLatency(value) => socket.set_peer_latency(value)?,
@@ -534,6 +772,10 @@ impl SrtAsyncStream {
     pub fn local_addr(&self) -> Result<SocketAddr> {
         self.socket.local_addr()
     }
+    /// For statistics and options while a task owns the stream for I/O.
+    pub fn handle(&self) -> SrtHandle {
+        SrtHandle::new(self.socket)
+    }
     pub fn peer_addr(&self) -> Result<SocketAddr> {
         self.socket.peer_addr()
     }
@@ -585,6 +827,27 @@ impl SrtAsyncStream {
     pub fn get_nak_report(&self) -> Result<bool> {
         self.socket.get_nak_report()
     }
+    /// The filter agreed with the peer, if any.
+    pub fn get_packet_filter(&self) -> Result<Option<PacketFilter>> {
+        let filter = self.socket.get_packet_filter()?;
+        if filter.is_empty() {
+            return Ok(None);
+        }
+        filter
+            .parse()
+            .map(Some)
+            .map_err(|_| SrtErrorKind::InvParam.into())
+    }
+    pub fn filter_stats(&self) -> Result<FilterStats> {
+        FilterStats::of(&self.socket)
+    }
+    /// Connection statistics; the interval counters restart on every call.
+    pub fn bistats(&self) -> Result<srt::SRT_TRACEBSTATS> {
+        self.socket.bistats()
+    }
+    pub fn get_crypto_mode(&self) -> Result<SrtCryptoMode> {
+        self.socket.get_crypto_mode()
+    }
     pub fn get_encryption_key_length(&self) -> Result<i32> {
         self.socket.get_encryption_key_length()
     }
@@ -627,6 +890,12 @@ impl SrtAsyncStream {
     pub fn get_send_km_state(&self) -> Result<SrtKmState> {
         self.socket.get_send_km_state()
     }
+    pub fn encryption_health(&self) -> Result<EncryptionHealth> {
+        EncryptionHealth::of(&self.socket)
+    }
+    pub fn encryption_monitor(&self) -> EncryptionMonitor {
+        EncryptionMonitor::new(self.socket)
+    }
     pub fn get_send_blocking(&self) -> Result<bool> {
         self.socket.get_send_blocking()
     }
@@ -662,6 +931,55 @@ impl SrtAsyncStream {
             })
         }
     }
+    /// Resolves once all sent data has been acknowledged by the peer and the
+    /// stream is closed. The stream is closed even if `timeout` expires first,
+    /// in which case `SrtErrorKind::Timeout` is returned.
+    pub fn shutdown(&self, timeout: Option<Duration>) -> ShutdownFuture {
+        ShutdownFuture {
+            socket: self.socket,
+            deadline: timeout.map(|timeout| Instant::now() + timeout),
+        }
+    }
+}
+
+pub struct ShutdownFuture {
+    socket: SrtSocket,
+    deadline: Option<Instant>,
+}
+
+impl Future for ShutdownFuture {
+    type Output = Result<()>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match poll_drain(&self.socket, cx, self.deadline) {
+            Poll::Ready(drained) => {
+                let closed = self.socket.close();
+                Poll::Ready(drained.and(closed))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+// The sender buffer shrinking has no epoll event of its own, so acknowledgement
+// progress is polled on a timer instead of spinning on SRT_EPOLL_OUT.
+fn poll_drain(
+    socket: &SrtSocket,
+    cx: &mut Context<'_>,
+    deadline: Option<Instant>,
+) -> Poll<Result<()>> {
+    match socket.is_drained() {
+        Ok(true) => Poll::Ready(Ok(())),
+        Ok(false) if shutdown::is_expired(deadline) => Poll::Ready(Err(SrtErrorKind::Timeout.into())),
+        Ok(false) => {
+            wake_after(cx, socket::DRAIN_POLL_INTERVAL);
+            Poll::Pending
+        }
+        Err(e) => Poll::Ready(Err(e)),
+    }
+}
+
+fn wake_after(cx: &mut Context<'_>, delay: Duration) {
+    timer::wake_at(Instant::now() + delay, cx.waker().clone());
 }
 
 pub struct RecvMsg2<T> {
@@ -682,8 +1000,8 @@ impl<T> Future for RecvMsg2<T>
             self.get_mut().state.as_mut().expect("RecvMsg2 polled after completion");
         match inner.socket.recvmsg2(inner.buf.as_mut()) {
             Ok((size, msg_ctrl)) => Poll::Ready(Ok((size, msg_ctrl))),
-            Err(e) => match e {
-                SrtError::AsyncRcv => {
+            Err(e) => match e.kind() {
+                SrtErrorKind::AsyncRcv => {
                     let waker = cx.waker().clone();
                     let mut epoll = Epoll::new()?;
                     epoll.add(&inner.socket, &srt::SRT_EPOLL_OPT::SRT_EPOLL_IN)?;
@@ -694,7 +1012,7 @@ impl<T> Future for RecvMsg2<T>
                     });
                     Poll::Pending
                 }
-                e => Poll::Ready(Err(e.into())),
+                _ => Poll::Ready(Err(e)),
             },
         }
     }
@@ -708,8 +1026,8 @@ impl AsyncRead for SrtAsyncStream {
     ) -> Poll<std::result::Result<usize, io::Error>> {
         match self.socket.recv(buf) {
             Ok(s) => Poll::Ready(Ok(s)),
-            Err(e) => match e {
-                SrtError::AsyncRcv => {
+            Err(e) => match e.kind() {
+                SrtErrorKind::AsyncRcv => {
                     let waker = cx.waker().clone();
                     let mut epoll = Epoll::new()?;
                     epoll.add(&self.socket, &srt::SRT_EPOLL_OPT::SRT_EPOLL_IN)?;
@@ -720,7 +1038,7 @@ impl AsyncRead for SrtAsyncStream {
                     });
                     Poll::Pending
                 }
-                e => Poll::Ready(Err(e.into())),
+                _ => Poll::Ready(Err(e.into())),
             },
         }
     }
@@ -734,8 +1052,8 @@ impl AsyncWrite for SrtAsyncStream {
     ) -> Poll<std::result::Result<usize, io::Error>> {
         match self.socket.send(buf) {
             Ok(s) => Poll::Ready(Ok(s)),
-            Err(e) => match e {
-                SrtError::AsyncSnd => match self.socket.get_sender_buffer() {
+            Err(e) => match e.kind() {
+                SrtErrorKind::AsyncSnd => match self.socket.get_sender_buffer() {
                     Ok((_blocks, bytes)) => {
                         if bytes == 0 {
                             Poll::Ready(Ok(0))
@@ -753,7 +1071,7 @@ impl AsyncWrite for SrtAsyncStream {
                     }
                     Err(e) => Poll::Ready(Err(e.into())),
                 },
-                e => Poll::Ready(Err(e.into())),
+                _ => Poll::Ready(Err(e.into())),
             },
         }
     }
@@ -784,26 +1102,15 @@ impl AsyncWrite for SrtAsyncStream {
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<std::result::Result<(), io::Error>> {
-        match self.socket.get_sender_buffer() {
-            Ok((_blocks, bytes)) => {
-                if bytes == 0 {
-                    Poll::Ready(match self.socket.close() {
-                        Ok(()) => Ok(()),
-                        Err(e) => Err(e.into()),
-                    })
-                } else {
-                    let waker = cx.waker().clone();
-                    let mut epoll = Epoll::new()?;
-                    epoll.add(&self.socket, &srt::SRT_EPOLL_OPT::SRT_EPOLL_OUT)?;
-                    thread::spawn(move || {
-                        if let Ok(_) = epoll.wait(-1) {
-                            waker.wake();
-                        }
-                    });
-                    Poll::Pending
-                }
+        match poll_drain(&self.socket, cx, None) {
+            Poll::Ready(drained) => {
+                let closed = self.socket.close();
+                Poll::Ready(match drained.and(closed) {
+                    Ok(()) => Ok(()),
+                    Err(e) => Err(e.into()),
+                })
             }
-            Err(e) => Poll::Ready(Err(e.into())),
+            Poll::Pending => Poll::Pending,
         }
     }
 }
@@ -827,9 +1134,43 @@ impl SrtAsyncListener {
     pub fn close(self) -> Result<()> {
         self.socket.close()
     }
+    /// Stops accepting; the returned future resolves once every connection
+    /// accepted from this listener has been closed or broken. Connections
+    /// still open after `timeout` are closed and `SrtErrorKind::Timeout` is
+    /// returned.
+    pub fn shutdown(self, timeout: Option<Duration>) -> Result<ListenerShutdownFuture> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        self.socket.close()?;
+        Ok(ListenerShutdownFuture {
+            listener: self.socket,
+            deadline,
+        })
+    }
     pub fn local_addr(&self) -> Result<SocketAddr> {
         self.socket.local_addr()
     }
+    pub fn handle(&self) -> SrtHandle {
+        SrtHandle::new(self.socket)
+    }
+}
+
+pub struct ListenerShutdownFuture {
+    listener: SrtSocket,
+    deadline: Option<Instant>,
+}
+
+impl Future for ListenerShutdownFuture {
+    type Output = Result<()>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if shutdown::accepted_from(self.listener).is_empty() {
+            Poll::Ready(Ok(()))
+        } else if shutdown::is_expired(self.deadline) {
+            Poll::Ready(shutdown::close_accepted(self.listener))
+        } else {
+            wake_after(cx, socket::DRAIN_POLL_INTERVAL);
+            Poll::Pending
+        }
+    }
 }
 
 impl Drop for SrtAsyncListener {
@@ -838,6 +1179,12 @@ impl Drop for SrtAsyncListener {
     }
 }
 
+fn async_stream(socket: SrtSocket) -> Result<SrtAsyncStream> {
+    socket.set_receive_blocking(false)?;
+    socket.set_send_blocking(false)?;
+    Ok(SrtAsyncStream { socket })
+}
+
 pub struct AcceptFuture {
     socket: SrtSocket,
 }
@@ -846,19 +1193,9 @@ impl Future for AcceptFuture {
     type Output = Result<(SrtAsyncStream, SocketAddr)>;
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         match self.socket.accept() {
-            Ok((socket, addr)) => {
-                let r_b = socket.set_receive_blocking(false);
-                let s_b = socket.set_send_blocking(false);
-                if r_b.is_err() {
-                    Poll::Ready(Err(r_b.expect_err("unreachable")))
-                } else if s_b.is_err() {
-                    Poll::Ready(Err(s_b.expect_err("unreachable")))
-                } else {
-                    Poll::Ready(Ok((SrtAsyncStream { socket }, addr)))
-                }
-            }
-            Err(e) => match e {
-                SrtError::AsyncRcv => {
+            Ok((socket, addr)) => Poll::Ready(async_stream(socket).map(|stream| (stream, addr))),
+            Err(e) => match e.kind() {
+                SrtErrorKind::AsyncRcv => {
                     let waker = cx.waker().clone();
                     let mut epoll = Epoll::new()?;
                     epoll.add(&self.socket, &srt::SRT_EPOLL_OPT::SRT_EPOLL_IN)?;
@@ -869,7 +1206,7 @@ impl Future for AcceptFuture {
                     });
                     Poll::Pending
                 }
-                e => Poll::Ready(Err(e)),
+                _ => Poll::Ready(Err(e)),
             },
         }
     }
@@ -877,41 +1214,41 @@ impl Future for AcceptFuture {
 
 pub struct ConnectFuture {
     socket: Option<SrtSocket>,
+    watch: connect_callback::Watch,
+}
+
+impl ConnectFuture {
+    fn watch(socket: SrtSocket) -> Result<Self> {
+        Ok(Self {
+            socket: Some(socket),
+            watch: connect_callback::watch(&socket)?,
+        })
+    }
 }
 
 impl Future for ConnectFuture {
     type Output = Result<SrtAsyncStream>;
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        match self.socket.unwrap().get_socket_state() {
-            Ok(s) => match s {
-                SrtSocketStatus::Connected => Poll::Ready(Ok(SrtAsyncStream {
-                    socket: self.socket.take().unwrap(),
-                })),
-                SrtSocketStatus::Broken => Poll::Ready(Err(SrtError::ConnLost)),
-                SrtSocketStatus::Init => Poll::Ready(Err(SrtError::UnboundSock)),
-                SrtSocketStatus::Opened => Poll::Ready(Err(SrtError::InvOp)),
-                SrtSocketStatus::Listening => Poll::Ready(Err(SrtError::InvOp)),
-                SrtSocketStatus::Connecting => match self.socket.unwrap().get_reject_reason() {
-                    error::SrtRejectReason::Unknown => {
-                        let waker = cx.waker().clone();
-                        let mut epoll = Epoll::new()?;
-                        let events =
-                            srt::SRT_EPOLL_OPT::SRT_EPOLL_OUT | srt::SRT_EPOLL_OPT::SRT_EPOLL_ERR;
-                        epoll.add(&self.socket.unwrap(), &events)?;
-                        thread::spawn(move || {
-                            if let Ok(_) = epoll.wait(-1) {
-                                waker.wake();
-                            }
-                        });
-                        Poll::Pending
+        let socket = self.socket.unwrap();
+        // Failures wake through the callback, success through the socket
+        // becoming writable.
+        connect_callback::lock(&self.watch).waker = Some(cx.waker().clone());
+        match connect_callback::state(&socket, &self.watch) {
+            ConnectState::Connected => Poll::Ready(Ok(SrtAsyncStream {
+                socket: self.socket.take().unwrap(),
+            })),
+            ConnectState::Failed(e) => Poll::Ready(Err(e)),
+            ConnectState::Connecting => {
+                let waker = cx.waker().clone();
+                let mut epoll = Epoll::new()?;
+                epoll.add(&socket, &srt::SRT_EPOLL_OPT::SRT_EPOLL_OUT)?;
+                thread::spawn(move || {
+                    if epoll.wait(-1).is_ok() {
+                        waker.wake();
                     }
-                    r => Poll::Ready(Err(SrtError::ConnRej(r))),
-                },
-                SrtSocketStatus::Closing => Poll::Ready(Err(SrtError::Closed)),
-                SrtSocketStatus::Closed => Poll::Ready(Err(SrtError::Closed)),
-                SrtSocketStatus::NonExist => Poll::Ready(Err(SrtError::InvSock)),
-            },
-            Err(e) => Poll::Ready(Err(e)),
+                });
+                Poll::Pending
+            }
         }
     }
 }
@@ -927,10 +1264,15 @@ pub struct SrtBoundAsyncSocket {
 
 impl SrtBoundAsyncSocket {
     pub fn connect<A: ToSocketAddrs>(self, remote: A) -> Result<ConnectFuture> {
-        self.socket.connect(remote)?;
+        let future = ConnectFuture::watch(self.socket)?;
         self.socket.set_receive_blocking(false)?;
-        Ok(ConnectFuture {
-            socket: Some(self.socket),
+        self.socket.connect(remote)?;
+        Ok(future)
+    }
+    pub fn listen(self, backlog: i32) -> Result<SrtAsyncListener> {
+        self.socket.listen(backlog)?;
+        Ok(SrtAsyncListener {
+            socket: self.socket,
         })
     }
     pub fn local_addr(&self) -> Result<SocketAddr> {
@@ -950,13 +1292,23 @@ impl SrtAsyncBuilder {
         let socket = socket.bind(local)?;
         Ok(SrtBoundAsyncSocket { socket })
     }
+    /// Like `bind`, but uses `udp` instead of creating a UDP socket. libsrt
+    /// takes ownership of `udp`.
+    pub fn bind_acquire(self, udp: UdpSocket) -> Result<SrtBoundAsyncSocket> {
+        let socket = SrtSocket::new()?;
+        self.config_socket(&socket)?;
+        socket.set_send_blocking(false)?;
+        let socket = socket.bind_acquire(udp)?;
+        Ok(SrtBoundAsyncSocket { socket })
+    }
     pub fn connect<A: ToSocketAddrs>(self, remote: A) -> Result<ConnectFuture> {
         let socket = SrtSocket::new()?;
         self.config_socket(&socket)?;
         socket.set_send_blocking(false)?;
-        socket.connect(remote)?;
+        let future = ConnectFuture::watch(socket)?;
         socket.set_receive_blocking(false)?;
-        Ok(ConnectFuture { socket: Some(socket) })
+        socket.connect(remote)?;
+        Ok(future)
     }
     pub fn listen<A: ToSocketAddrs>(self, addr: A, backlog: i32) -> Result<SrtAsyncListener> {
         let socket = SrtSocket::new()?;
@@ -965,14 +1317,50 @@ impl SrtAsyncBuilder {
         socket.listen(backlog)?; // Still synchronous
         Ok(SrtAsyncListener { socket })
     }
+    /// Listens on every address `addrs` resolves to instead of only the
+    /// first one. Fails, closing everything, unless all of them can be bound.
+    pub fn listen_all<A: ToSocketAddrs>(
+        self,
+        addrs: A,
+        backlog: i32,
+    ) -> std::result::Result<SrtAsyncMultiListener, BindError> {
+        let listeners = multi_listener::listen_all(addrs, |addr| {
+            SrtAsyncBuilder {
+                opt_vec: self.opt_vec.clone(),
+            }
+            .listen(addr, backlog)
+        })?;
+        Ok(SrtAsyncMultiListener::new(listeners))
+    }
+    /// Listens on `port` of both the IPv4 and the IPv6 wildcard address,
+    /// with the IPv6 socket set to IPv6 only.
+    pub fn listen_dual_stack(
+        self,
+        port: u16,
+        backlog: i32,
+    ) -> std::result::Result<SrtAsyncMultiListener, BindError> {
+        let builder = self.set_ipv6_only(1);
+        let listeners = multi_listener::listen_dual_stack(
+            port,
+            |addr| {
+                SrtAsyncBuilder {
+                    opt_vec: builder.opt_vec.clone(),
+                }
+                .listen(addr, backlog)
+            },
+            SrtAsyncListener::local_addr,
+        )?;
+        Ok(SrtAsyncMultiListener::new(listeners))
+    }
     pub fn rendezvous<A: ToSocketAddrs>(self, local: A, remote: A) -> Result<ConnectFuture> {
         let socket = SrtSocket::new()?;
         socket.set_rendezvous(true)?;
         self.config_socket(&socket)?;
         socket.set_send_blocking(false)?;
-        socket.rendezvous(local, remote)?;
+        let future = ConnectFuture::watch(socket)?;
         socket.set_receive_blocking(false)?;
-        Ok(ConnectFuture { socket: Some(socket) })
+        socket.rendezvous(local, remote)?;
+        Ok(future)
     }
 }
 
@@ -987,6 +1375,18 @@ impl SrtAsyncBuilder {
         self.opt_vec.push(SrtPreConnectOpt::ConnTimeO(msecs));
         self
     }
+    /// Calls `callback` with the outcome of every failed connection
+    /// attempt, including its reject reason. libsrt reports those of
+    /// non-blocking attempts only; socket groups, whose members it also
+    /// reports, are not supported by this crate.
+    pub fn set_connect_callback(
+        mut self,
+        callback: impl Fn(&ConnectEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.opt_vec
+            .push(SrtPreConnectOpt::ConnectCallback(Arc::new(callback)));
+        self
+    }
     pub fn set_flight_flag_size(mut self, packets: i32) -> Self {
         self.opt_vec.push(SrtPreConnectOpt::FC(packets));
         self
@@ -1039,11 +1439,11 @@ impl SrtAsyncBuilder {
         self.opt_vec.push(SrtPreConnectOpt::NakReport(enable));
         self
     }
-    pub fn set_packet_filter(mut self, filter: String) -> Self {
+    pub fn set_packet_filter(mut self, filter: PacketFilter) -> Self {
         self.opt_vec.push(SrtPreConnectOpt::PacketFilter(filter));
         self
     }
-    pub fn set_passphrase(mut self, passphrase: String) -> Self {
+    pub fn set_passphrase(mut self, passphrase: Passphrase) -> Self {
         self.opt_vec.push(SrtPreConnectOpt::Passphrase(passphrase));
         self
     }
@@ -1051,6 +1451,10 @@ impl SrtAsyncBuilder {
         self.opt_vec.push(SrtPreConnectOpt::PayloadSize(bytes));
         self
     }
+    pub fn set_crypto_mode(mut self, mode: SrtCryptoMode) -> Self {
+        self.opt_vec.push(SrtPreConnectOpt::CryptoMode(mode));
+        self
+    }
     pub fn set_encryption_key_length(mut self, bytes: i32) -> Self {
         self.opt_vec.push(SrtPreConnectOpt::PBKeyLen(bytes));
         self
@@ -1139,11 +1543,20 @@ impl SrtAsyncBuilder {
         self
     }
     fn config_socket(self, socket: &SrtSocket) -> Result<()> {
+        // A half configured socket is of no use to the caller.
+        self.apply_opts(socket).inspect_err(|_| {
+            let _ = socket.close();
+        })
+    }
+    fn apply_opts(self, socket: &SrtSocket) -> Result<()> {
         for opt in self.opt_vec {
             match opt {
                 #[cfg(target_family = "unix")]
                 SrtPreConnectOpt::BindToDevice(value) => socket.set_bind_to_device(value)?,
                 SrtPreConnectOpt::ConnTimeO(value) => socket.set_connection_timeout(value)?,
+                SrtPreConnectOpt::ConnectCallback(callback) => {
+                    connect_callback::add(socket, callback)?
+                }
                 SrtPreConnectOpt::FC(value) => socket.set_flight_flag_size(value)?,
                 SrtPreConnectOpt::IpTos(value) => socket.set_ip_type_of_service(value)?,
                 SrtPreConnectOpt::IpTtl(value) => socket.set_ipv4_time_to_live(value)?,
@@ -1157,9 +1570,12 @@ impl SrtAsyncBuilder {
                 SrtPreConnectOpt::MinVersion(value) => socket.set_min_version(value)?,
                 SrtPreConnectOpt::Mss(value) => socket.set_mss(value)?,
                 SrtPreConnectOpt::NakReport(value) => socket.set_nak_report(value)?,
-                SrtPreConnectOpt::PacketFilter(value) => socket.set_packet_filter(&value)?,
-                SrtPreConnectOpt::Passphrase(value) => socket.set_passphrase(&value)?,
+                SrtPreConnectOpt::PacketFilter(value) => {
+                    socket.set_packet_filter(&value.to_string())?
+                }
+                SrtPreConnectOpt::Passphrase(value) => socket.set_passphrase(value.as_str())?,
                 SrtPreConnectOpt::PayloadSize(value) => socket.set_payload_size(value)?,
+                SrtPreConnectOpt::CryptoMode(value) => socket.set_crypto_mode(value)?,
                 SrtPreConnectOpt::PBKeyLen(value) => socket.set_encryption_key_length(value)?,
                 SrtPreConnectOpt::PeerIdleTimeO(value) => socket.set_peer_idle_timeout(value)?,
                 SrtPreConnectOpt::PeerLatency(value) => socket.set_peer_latency(value)?,
@@ -1196,6 +1612,7 @@ enum SrtPreConnectOpt {
     #[cfg(target_family = "unix")]
     BindToDevice(String),
     ConnTimeO(i32),
+    ConnectCallback(connect_callback::ConnectCallback),
     FC(i32),
     IpTos(i32),
     IpTtl(i32),
@@ -1209,9 +1626,10 @@ enum SrtPreConnectOpt {
     MinVersion(i32),
     Mss(i32),
     NakReport(bool),
-    PacketFilter(String),
-    Passphrase(String),
+    PacketFilter(PacketFilter),
+    Passphrase(Passphrase),
     PayloadSize(i32),
+    CryptoMode(SrtCryptoMode),
     PBKeyLen(i32),
     PeerIdleTimeO(i32),
     PeerLatency(i32),
@@ -1301,7 +1719,8 @@ impl Epoll {
                 .map(|event| {
                     (
                         SrtSocket { id: event.fd },
-                        srt::SRT_EPOLL_OPT(event.events.try_into().expect("invalid events")),
+                        // A bit mask, so the sign bit is just another flag.
+                        srt::SRT_EPOLL_OPT(event.events as u32),
                     )
                 })
                 .collect())
@@ -1338,6 +1757,7 @@ mod tests {
         net::SocketAddr,
         sync::mpsc,
         thread,
+        time::Duration,
     };
 
     #[test]
@@ -1473,6 +1893,1108 @@ mod tests {
         srt::cleanup().expect("failed cleanup()");
     }
 
+    #[test]
+    fn test_context_keeps_library_started() {
+        let context = srt::SrtContext::new().expect("fail context");
+        let listen = srt::builder()
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        drop(context.clone());
+        drop(context);
+        assert!(listen.local_addr().is_ok());
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    // The crypto features only choose how the vendored libsrt is built; a
+    // system libsrt has whatever support it was built with.
+    #[cfg(feature = "vendored")]
+    fn test_encryption_available() {
+        assert_eq!(srt::encryption_available(), !cfg!(feature = "crypto-none"));
+    }
+    #[test]
+    fn test_crypto_mode_needs_aead_api() {
+        let socket = srt::SrtSocket::new().expect("fail new()");
+        let result = socket.set_crypto_mode(srt::SrtCryptoMode::AesCtr);
+        assert_eq!(result.is_ok(), libsrt_sys::AEAD_API);
+        if let Err(e) = result {
+            assert_eq!(e.kind(), srt::error::SrtErrorKind::InvOp);
+        }
+        assert!(socket.close().is_ok());
+    }
+    #[test]
+    #[cfg(feature = "crypto-openssl-evp")]
+    fn test_aes_gcm_negotiated() {
+        let listen = srt::builder()
+            .set_passphrase(
+                srt::Passphrase::new("gcm-passphrase").expect("invalid passphrase"),
+            )
+            .set_crypto_mode(srt::SrtCryptoMode::Auto)
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let accept = thread::spawn(move || {
+            let (peer, _peer_addr) = listen.accept().expect("fail accept()");
+            let mode = peer.get_crypto_mode().expect("fail get_crypto_mode()");
+            assert!(peer.close().is_ok());
+            assert!(listen.close().is_ok());
+            mode
+        });
+        let connect = srt::builder()
+            .set_passphrase(
+                srt::Passphrase::new("gcm-passphrase").expect("invalid passphrase"),
+            )
+            .set_crypto_mode(srt::SrtCryptoMode::AesGcm)
+            .connect(addr)
+            .expect("fail connect()");
+        assert_eq!(
+            connect.get_crypto_mode().expect("fail get_crypto_mode()"),
+            srt::SrtCryptoMode::AesGcm
+        );
+        assert_eq!(
+            accept.join().expect("accept thread panicked"),
+            srt::SrtCryptoMode::AesGcm
+        );
+        assert!(connect.close().is_ok());
+    }
+    #[test]
+    fn test_connect_callback_rejected() {
+        if !srt::encryption_available() {
+            return;
+        }
+        let listen = srt::async_builder()
+            .set_passphrase(
+                srt::Passphrase::new("listener-passphrase").expect("invalid passphrase"),
+            )
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let (tx, rx) = mpsc::channel();
+        let tx = std::sync::Mutex::new(tx);
+        let connect = srt::async_builder()
+            .set_passphrase(
+                srt::Passphrase::new("caller-passphrase").expect("invalid passphrase"),
+            )
+            .set_connect_callback(move |event| {
+                let _ = tx.lock().unwrap().send(event.clone());
+            })
+            .connect(addr)
+            .expect("fail connect()");
+        let connected = block_on(connect);
+        assert_eq!(
+            connected.map(|_| ()).map_err(|e| e.kind()),
+            Err(srt::error::SrtErrorKind::ConnRej(
+                srt::error::SrtRejectReason::BadSecret
+            ))
+        );
+        let event = rx
+            .recv_timeout(Duration::from_secs(1))
+            .expect("no connect event");
+        assert_eq!(
+            event.result.map_err(|e| e.kind()),
+            Err(srt::error::SrtErrorKind::ConnRej(
+                srt::error::SrtRejectReason::BadSecret
+            ))
+        );
+        assert_eq!(event.peer, Some(addr));
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_connect_callback_sync() {
+        if !srt::encryption_available() {
+            return;
+        }
+        let listen = srt::builder()
+            .set_passphrase(
+                srt::Passphrase::new("listener-passphrase").expect("invalid passphrase"),
+            )
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let (tx, rx) = mpsc::channel();
+        let tx = std::sync::Mutex::new(tx);
+        let connecting = srt::builder()
+            .set_passphrase(
+                srt::Passphrase::new("caller-passphrase").expect("invalid passphrase"),
+            )
+            .set_connect_callback(move |event| {
+                let _ = tx.lock().unwrap().send(event.clone());
+            })
+            .start_connect(addr)
+            .expect("fail start_connect()");
+        match connecting.wait(None).expect("fail wait()") {
+            srt::ConnectState::Failed(e) => assert_eq!(
+                e.kind(),
+                srt::error::SrtErrorKind::ConnRej(srt::error::SrtRejectReason::BadSecret)
+            ),
+            state => panic!("expected a rejection, got {:?}", state),
+        }
+        let event = rx
+            .recv_timeout(Duration::from_secs(1))
+            .expect("no connect event");
+        assert_eq!(
+            event.result.map_err(|e| e.kind()),
+            Err(srt::error::SrtErrorKind::ConnRej(
+                srt::error::SrtRejectReason::BadSecret
+            ))
+        );
+        // libsrt may already have closed the broken socket.
+        drop(connecting);
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_start_connect() {
+        let listen = srt::builder()
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let connecting = srt::builder()
+            .start_connect(addr)
+            .expect("fail start_connect()");
+        let (peer, _peer_addr) = listen.accept().expect("fail accept()");
+        assert!(matches!(
+            connecting
+                .wait(Some(Duration::from_secs(1)))
+                .expect("fail wait()"),
+            srt::ConnectState::Connected
+        ));
+        let mut connect = connecting.into_stream().expect("fail into_stream()");
+        connect.write_all(b"started").expect("fail write()");
+        assert!(connect.close().is_ok());
+        assert!(peer.close().is_ok());
+
+        // Nobody answers: still connecting after the wait, then cancelled.
+        let silent = std::net::UdpSocket::bind("127.0.0.1:0").expect("fail bind()");
+        let connecting = srt::builder()
+            .start_connect(silent.local_addr().expect("fail local_addr()"))
+            .expect("fail start_connect()");
+        assert!(matches!(
+            connecting
+                .wait(Some(Duration::from_millis(100)))
+                .expect("fail wait()"),
+            srt::ConnectState::Connecting
+        ));
+        assert!(connecting.cancel().is_ok());
+        assert!(listen.close().is_ok());
+    }
+    // Forwards between hosts `a` and `b` so that each sees the other at a
+    // different port, like a port-mapping NAT between them. Returns where `a`
+    // reaches `b` and where `b` reaches `a`.
+    fn nat_proxy(a: SocketAddr, b: SocketAddr) -> (SocketAddr, SocketAddr) {
+        let a_side = std::net::UdpSocket::bind("127.0.0.1:0").expect("fail bind()");
+        let b_side = std::net::UdpSocket::bind("127.0.0.1:0").expect("fail bind()");
+        let mapped = (
+            a_side.local_addr().expect("fail local_addr()"),
+            b_side.local_addr().expect("fail local_addr()"),
+        );
+        let forward = |from: std::net::UdpSocket, to: std::net::UdpSocket, inside, outside| {
+            thread::spawn(move || {
+                let mut buf = [0; 1500];
+                while let Ok((len, sender)) = from.recv_from(&mut buf) {
+                    if sender == inside {
+                        let _ = to.send_to(&buf[..len], outside);
+                    }
+                }
+            });
+        };
+        let a_out = a_side.try_clone().expect("fail try_clone()");
+        let b_out = b_side.try_clone().expect("fail try_clone()");
+        forward(a_side, b_out, a, b);
+        forward(b_side, a_out, b, a);
+        mapped
+    }
+    fn free_addr() -> SocketAddr {
+        std::net::UdpSocket::bind("127.0.0.1:0")
+            .and_then(|socket| socket.local_addr())
+            .expect("fail bind()")
+    }
+    #[test]
+    fn test_rendezvous_through_nat() {
+        use srt::rendezvous::{LocalSignaling, Rendezvous};
+
+        let (a_local, b_local) = (free_addr(), free_addr());
+        let (b_mapped, a_mapped) = nat_proxy(a_local, b_local);
+        // Offered but unreachable, like an address behind the NAT.
+        let unreachable = std::net::UdpSocket::bind("127.0.0.1:0").expect("fail bind()");
+        let unreachable = unreachable.local_addr().expect("fail local_addr()");
+        let (mut a_signaling, mut b_signaling) = LocalSignaling::pair();
+        let any = |addr: SocketAddr| SocketAddr::from(([0, 0, 0, 0], addr.port()));
+        let b = thread::spawn(move || {
+            Rendezvous::new(srt::builder(), any(b_local))
+                .expect("fail new()")
+                .add_candidate(unreachable)
+                .add_candidate(b_mapped)
+                .connect(&mut b_signaling)
+                .expect("fail connect()")
+        });
+        let (mut a_stream, a_peer) = Rendezvous::new(srt::builder(), any(a_local))
+            .expect("fail new()")
+            .add_candidate(a_mapped)
+            .add_candidate(unreachable)
+            .connect(&mut a_signaling)
+            .expect("fail connect()");
+        let (mut b_stream, b_peer) = b.join().expect("rendezvous thread panicked");
+        assert_eq!(a_peer, b_mapped);
+        assert_eq!(b_peer, a_mapped);
+        a_stream.write_all(b"punched").expect("fail write()");
+        let mut buf = [0; 16];
+        let len = b_stream.read(&mut buf).expect("fail read()");
+        assert_eq!(&buf[..len], b"punched");
+        assert!(a_stream.close().is_ok());
+        assert!(b_stream.close().is_ok());
+    }
+    #[test]
+    fn test_rendezvous_needs_message_api() {
+        use srt::{error::SrtErrorKind, rendezvous::{Rendezvous, RendezvousError}};
+
+        let local = SocketAddr::from(([0, 0, 0, 0], 0));
+        let stream = srt::builder().set_file_transmission_type();
+        match Rendezvous::new(stream, local) {
+            Err(RendezvousError::Srt(e)) => assert_eq!(e.kind(), SrtErrorKind::InvParam),
+            _ => panic!("stream mode accepted"),
+        }
+        let message = srt::builder()
+            .set_file_transmission_type()
+            .set_message_api(true);
+        assert!(Rendezvous::new(message, local).is_ok());
+    }
+    #[test]
+    #[cfg(feature = "testing")]
+    fn test_mock_transport() {
+        use srt::{
+            error::{SrtErrorKind, SrtRejectReason},
+            transport::{Connector, Listener, MockConnector, MockListener, MockStream, Stream},
+        };
+
+        // Echoes every message back, as application code under test would.
+        async fn echo<L: Listener>(listener: &L) -> Result<usize, std::io::Error> {
+            let (mut stream, _peer) = listener.accept().await?;
+            let mut buf = [0; 16];
+            let mut echoed = 0;
+            loop {
+                let len = stream.read(&mut buf).await?;
+                if len == 0 {
+                    return Ok(echoed);
+                }
+                stream.write_all(&buf[..len]).await?;
+                echoed += 1;
+            }
+        }
+
+        let peer: SocketAddr = "192.0.2.1:9000".parse().unwrap();
+        let stream = MockStream::new()
+            .with_addrs("127.0.0.1:9000".parse().unwrap(), peer)
+            .with_stream_id("live")
+            .message(*b"one")
+            .delay(Duration::from_millis(10))
+            .message(*b"two");
+        let written = stream.written();
+        let listener = MockListener::new("127.0.0.1:9000".parse().unwrap()).incoming(stream);
+        assert_eq!(block_on(echo(&listener)).expect("fail echo()"), 2);
+        assert_eq!(written.messages(), vec![b"one".to_vec(), b"two".to_vec()]);
+
+        let stream = MockStream::new()
+            .message(*b"one")
+            .disconnect(SrtErrorKind::ConnLost);
+        let listener = MockListener::new("127.0.0.1:9000".parse().unwrap()).incoming(stream);
+        let e = block_on(echo(&listener)).expect_err("disconnect not reported");
+        assert_eq!(e.kind(), std::io::ErrorKind::ConnectionAborted);
+
+        let connector = MockConnector::new()
+            .reject(SrtRejectReason::BadSecret)
+            .accept(MockStream::new().with_stream_id("live"));
+        assert_eq!(
+            block_on(connector.connect(peer)).map(|_| ()).map_err(|e| e.kind()),
+            Err(SrtErrorKind::ConnRej(SrtRejectReason::BadSecret))
+        );
+        let stream = block_on(connector.connect(peer)).expect("fail connect()");
+        assert_eq!(stream.peer_addr().expect("fail peer_addr()"), peer);
+        assert_eq!(stream.stream_id().expect("fail stream_id()"), "live");
+        assert_eq!(connector.attempts(), vec![peer, peer]);
+    }
+    #[test]
+    fn test_reconnecting_stream() {
+        let listen = srt::async_builder()
+            .listen("127.0.0.1:0", 2)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let mut stream = srt::ReconnectingStream::new(
+            srt::async_builder(),
+            addr,
+            srt::ReconnectConfig::default(),
+        )
+        .expect("fail new()");
+        let mut events = stream.events();
+        let mut buf = [0; 16];
+        block_on(async {
+            let (accepted, written) =
+                future::join(listen.accept(), stream.write_all(b"first")).await;
+            let (mut peer, _peer_addr) = accepted.expect("fail accept()");
+            written.expect("fail write()");
+            let len = peer.read(&mut buf).await.expect("fail read()");
+            assert_eq!(&buf[..len], b"first");
+            peer.close().await.expect("fail close()");
+
+            let reaccept = async {
+                let (mut peer, _peer_addr) = listen.accept().await.expect("fail accept()");
+                peer.write_all(b"second").await.expect("fail write()");
+                peer
+            };
+            let (mut peer, read) = future::join(reaccept, stream.read(&mut buf)).await;
+            let len = read.expect("fail read()");
+            assert_eq!(&buf[..len], b"second");
+            peer.close().await.expect("fail close()");
+            stream.close().await.expect("fail close()");
+        });
+        assert!(matches!(events.try_recv(), Ok(srt::ReconnectEvent::Connected)));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(srt::ReconnectEvent::Disconnected(_))
+        ));
+        assert!(matches!(events.try_recv(), Ok(srt::ReconnectEvent::Connected)));
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_encryption_monitor_bad_secret() {
+        if !srt::encryption_available() {
+            return;
+        }
+        let listen = srt::builder()
+            .set_passphrase(
+                srt::Passphrase::new("listener-passphrase").expect("invalid passphrase"),
+            )
+            .set_enforced_encryption(false)
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let accept = thread::spawn(move || {
+            let (peer, _peer_addr) = listen.accept().expect("fail accept()");
+            (listen, peer)
+        });
+        let connect = srt::builder()
+            .set_passphrase(
+                srt::Passphrase::new("caller-passphrase").expect("invalid passphrase"),
+            )
+            .set_enforced_encryption(false)
+            .connect(addr)
+            .expect("fail connect()");
+        let (listen, peer) = accept.join().expect("accept thread panicked");
+        let mut monitor = connect.encryption_monitor();
+        assert_eq!(
+            monitor.poll().expect("fail poll()"),
+            vec![srt::EncryptionEvent::BadSecret]
+        );
+        assert_eq!(monitor.poll().expect("fail poll()"), vec![]);
+        assert!(connect.close().is_ok());
+        assert!(peer.close().is_ok());
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_passphrase() {
+        assert!(srt::Passphrase::new("too-short").is_err());
+        assert!(srt::Passphrase::new("x".repeat(80)).is_err());
+        let passphrase = srt::Passphrase::new("x".repeat(79)).expect("invalid passphrase");
+        assert!(!format!("{:?}", passphrase).contains('x'));
+
+        let path = std::env::temp_dir().join(format!("srt-passphrase-{}", std::process::id()));
+        std::fs::write(&path, "from-file-passphrase\n").expect("fail write()");
+        let passphrase = srt::Passphrase::from_file(&path);
+        std::fs::remove_file(&path).expect("fail remove_file()");
+        assert_eq!(
+            passphrase.expect("fail from_file()").as_str(),
+            "from-file-passphrase"
+        );
+        std::fs::write(&path, "x".repeat(100)).expect("fail write()");
+        let passphrase = srt::Passphrase::from_file(&path);
+        assert!(matches!(passphrase, Err(srt::PassphraseError::Length(100))));
+        std::fs::write(&path, b"secret-\xff-passphrase").expect("fail write()");
+        let error = srt::Passphrase::from_file(&path).expect_err("fail from_file()");
+        std::fs::remove_file(&path).expect("fail remove_file()");
+        assert!(!format!("{} {:?}", error, error).contains("secret"));
+        assert!(matches!(
+            srt::Passphrase::from_env("SRT_RS_TEST_UNSET_PASSPHRASE"),
+            Err(srt::PassphraseError::NotPresent)
+        ));
+
+        #[cfg(unix)]
+        {
+            use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+            let key = "SRT_RS_TEST_NOT_UNICODE_PASSPHRASE";
+            std::env::set_var(key, OsStr::from_bytes(b"secret-\xff-passphrase"));
+            let error = srt::Passphrase::from_env(key).expect_err("fail from_env()");
+            std::env::remove_var(key);
+            assert!(matches!(error, srt::PassphraseError::NotUnicode));
+            assert!(!format!("{} {:?}", error, error).contains("secret"));
+        }
+    }
+    #[test]
+    fn test_concurrent_stream_access() {
+        use std::sync::Arc;
+
+        const WRITERS: u8 = 4;
+        const MESSAGES: u32 = 500;
+        let listen = srt::builder()
+            .set_receive_latency(500)
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let connect = thread::spawn(move || {
+            srt::builder()
+                .set_peer_latency(500)
+                .connect(addr)
+                .expect("fail connect()")
+        });
+        let (peer, _peer_addr) = listen.accept().expect("fail accept()");
+        let caller = Arc::new(connect.join().expect("connect thread panicked"));
+        peer.set_receive_timeout(2000)
+            .expect("fail set_receive_timeout()");
+
+        let writers: Vec<_> = (0..WRITERS)
+            .map(|writer| {
+                let caller = caller.clone();
+                thread::spawn(move || {
+                    let mut message = [writer; 8];
+                    for i in 0..MESSAGES {
+                        message[4..].copy_from_slice(&i.to_be_bytes());
+                        (&*caller).write_all(&message).expect("fail write()");
+                        thread::sleep(Duration::from_micros(200));
+                    }
+                })
+            })
+            .collect();
+        // Statistics and state from more threads, while the others write.
+        let handle = caller.handle();
+        let done = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let pollers: Vec<_> = (0..2)
+            .map(|_| {
+                let handle = handle.clone();
+                let done = done.clone();
+                thread::spawn(move || {
+                    while !done.load(std::sync::atomic::Ordering::Relaxed) {
+                        handle.stats().expect("fail stats()");
+                        handle.get_socket_state().expect("fail get_socket_state()");
+                        handle.get_send_data().expect("fail get_send_data()");
+                    }
+                })
+            })
+            .collect();
+
+        let mut next = [0u32; WRITERS as usize];
+        let mut buf = [0; 1500];
+        for _ in 0..WRITERS as u32 * MESSAGES {
+            let len = (&peer).read(&mut buf).expect("fail read()");
+            assert_eq!(len, 8);
+            let writer = buf[0] as usize;
+            // Messages from one writer stay in order and whole.
+            assert_eq!(&buf[..4], &[buf[0]; 4]);
+            assert_eq!(buf[4..8], next[writer].to_be_bytes());
+            next[writer] += 1;
+        }
+        for writer in writers {
+            writer.join().expect("writer thread panicked");
+        }
+        done.store(true, std::sync::atomic::Ordering::Relaxed);
+        for poller in pollers {
+            poller.join().expect("poller thread panicked");
+        }
+        let stats = handle.stats().expect("fail stats()");
+        assert!(stats.pktSentTotal >= i64::from(WRITERS as u32 * MESSAGES));
+
+        let caller = Arc::try_unwrap(caller).unwrap_or_else(|_| panic!("caller still shared"));
+        assert!(caller.close().is_ok());
+        // The handle outlives the stream, but not the connection.
+        assert!(!matches!(
+            handle.get_socket_state(),
+            Ok(srt::SrtSocketStatus::Connected)
+        ));
+        assert_eq!(
+            handle.stats().map(|_| ()).map_err(|e| e.kind()),
+            Err(srt::error::SrtErrorKind::InvSock)
+        );
+        assert!(peer.close().is_ok());
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_forward_source_time() {
+        use srt::forward::Forwarder;
+        use std::sync::mpsc;
+
+        const MESSAGES: usize = 20;
+        // The last hop's latency covers the first one, so the receiver
+        // delivers 300ms after the source sent rather than 100ms + 300ms.
+        let ingress = srt::builder()
+            .set_receive_latency(100)
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let ingress_addr = ingress.local_addr().expect("fail local_addr()");
+        let egress = srt::builder()
+            .set_receive_latency(300)
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let egress_addr = egress.local_addr().expect("fail local_addr()");
+        let source = thread::spawn(move || {
+            srt::builder()
+                .set_peer_latency(100)
+                .connect(ingress_addr)
+                .expect("fail connect()")
+        });
+        let (input, _) = ingress.accept().expect("fail accept()");
+        let source = source.join().expect("connect thread panicked");
+        let output = thread::spawn(move || {
+            srt::builder()
+                .set_peer_latency(300)
+                .connect(egress_addr)
+                .expect("fail connect()")
+        });
+        let (sink, _) = egress.accept().expect("fail accept()");
+        let output = output.join().expect("connect thread panicked");
+
+        let forwarder = Forwarder::new();
+        let stats = forwarder.stats();
+        let (tx, rx) = mpsc::channel();
+        let relay = thread::spawn(move || {
+            let result = forwarder.run(&input, &output, |forwarded| {
+                let _ = tx.send(*forwarded);
+            });
+            (result, input, output)
+        });
+        for i in 0..MESSAGES {
+            (&source).write_all(&[i as u8; 188]).expect("fail write()");
+            thread::sleep(Duration::from_millis(5));
+        }
+        let mut buf = [0; 1500];
+        for i in 0..MESSAGES {
+            let (len, ctrl) = sink.recvmsg2(&mut buf).expect("fail recvmsg2()");
+            assert_eq!(&buf[..len], &[i as u8; 188][..]);
+            let src_time = ctrl.src_time.expect("no source time");
+            let delay = srt::time_now() - src_time.get();
+            assert!((280_000..380_000).contains(&delay), "delay {}us", delay);
+        }
+        for _ in 0..MESSAGES {
+            let forwarded = rx.recv().expect("fail recv()");
+            assert!(!forwarded.restamped);
+            assert!(forwarded.delay.expect("no delay") >= Duration::from_millis(90));
+        }
+        assert_eq!(stats.messages(), MESSAGES as u64);
+        assert_eq!(stats.bytes(), MESSAGES as u64 * 188);
+
+        // Closing the source ends the relay without an error.
+        assert!(source.close().is_ok());
+        let (result, input, output) = relay.join().expect("relay thread panicked");
+        assert!(result.is_ok());
+        assert!(input.close().is_ok());
+        assert!(output.close().is_ok());
+        assert!(sink.close().is_ok());
+        assert!(ingress.close().is_ok());
+        assert!(egress.close().is_ok());
+    }
+    #[test]
+    fn test_unknown_codes() {
+        use srt::error::{handle_result, SrtErrorKind, SrtRejectReason};
+
+        assert_eq!(
+            SrtErrorKind::from(libsrt_sys::SRT_ERRNO(-12345)),
+            SrtErrorKind::Other(-12345)
+        );
+        assert_eq!(
+            handle_result((), 7).map_err(|e| e.kind()),
+            Err(SrtErrorKind::Unknown)
+        );
+        assert_eq!(
+            SrtRejectReason::from(libsrt_sys::SRT_REJECT_REASON(1403)),
+            SrtRejectReason::Other(1403)
+        );
+        // A value libsrt refuses fails the whole listen.
+        assert!(srt::builder()
+            .set_payload_size(-1)
+            .listen("127.0.0.1:0", 1)
+            .is_err());
+    }
+    #[test]
+    fn test_error_details() {
+        use srt::error::SrtErrorKind;
+
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").expect("fail bind()");
+        let addr = udp.local_addr().expect("fail local_addr()");
+        let e = match srt::builder().listen(addr, 1) {
+            Err(e) => e,
+            Ok(_) => panic!("expected the port to be taken"),
+        };
+        assert_eq!(e.kind(), SrtErrorKind::SockFail);
+        assert!(e.sys_errno().is_some());
+        assert!(e.message().is_some());
+        let e = std::io::Error::from(e);
+        assert_eq!(e.kind(), std::io::ErrorKind::AddrInUse);
+        assert!(e.get_ref().and_then(|e| e.source()).is_some());
+    }
+    #[test]
+    fn test_packet_filter_round_trip() {
+        let fec = srt::FecConfig::new(10)
+            .and_then(|fec| fec.set_rows(5))
+            .expect("invalid FEC config")
+            .set_layout(srt::FecLayout::Staircase)
+            .set_arq(srt::FecArq::OnReq);
+        let filter = srt::PacketFilter::Fec(fec);
+        let config = filter.to_string();
+        assert_eq!(config, "fec,cols:10,rows:5,layout:staircase,arq:onreq");
+        assert_eq!(config.parse::<srt::PacketFilter>(), Ok(filter));
+        assert!(srt::FecConfig::new(0).is_err());
+        assert!("fec,rows:5".parse::<srt::PacketFilter>().is_err());
+        assert!("fec,cols:10,layout:diagonal".parse::<srt::PacketFilter>().is_err());
+    }
+    #[test]
+    fn test_packet_filter_negotiated() {
+        let fec = srt::FecConfig::new(4)
+            .and_then(|fec| fec.set_rows(2))
+            .expect("invalid FEC config");
+        let listen = srt::builder()
+            .set_packet_filter(srt::PacketFilter::Fec(fec))
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let accept = thread::spawn(move || {
+            let (peer, _peer_addr) = listen.accept().expect("fail accept()");
+            (listen, peer)
+        });
+        let connect = srt::builder()
+            .set_packet_filter(srt::PacketFilter::Fec(
+                srt::FecConfig::new(4).expect("invalid FEC config"),
+            ))
+            .connect(addr)
+            .expect("fail connect()");
+        let (listen, peer) = accept.join().expect("accept thread panicked");
+        match connect.get_packet_filter().expect("fail get_packet_filter()") {
+            Some(srt::PacketFilter::Fec(fec)) => {
+                assert_eq!(fec.cols(), 4);
+                assert_eq!(fec.rows(), Some(2));
+            }
+            None => panic!("no packet filter negotiated"),
+        }
+        assert_eq!(connect.filter_stats().expect("fail filter_stats()").lost, 0);
+        assert!(connect.close().is_ok());
+        assert!(peer.close().is_ok());
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_udp_to_srt_gateway() {
+        let listen = srt::builder()
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let accept = thread::spawn(move || {
+            let (peer, _peer_addr) = listen.accept().expect("fail accept()");
+            (listen, peer)
+        });
+        let mut connect = srt::builder().connect(addr).expect("fail connect()");
+        let (listen, mut peer) = accept.join().expect("accept thread panicked");
+
+        let receiver = srt::gateway::UdpReceiver::bind("127.0.0.1:0").expect("fail bind()");
+        let udp_addr = receiver.local_addr().expect("fail local_addr()");
+        // Only a full payload is sent before the flush time, so the packing
+        // does not depend on how fast the datagrams arrive.
+        let config = srt::gateway::GatewayConfig {
+            flush_after: Duration::from_secs(5),
+            ..Default::default()
+        };
+        let gateway = srt::gateway::UdpToSrt::new(receiver, config);
+        let stats = gateway.stats();
+        let forward = thread::spawn(move || gateway.run(&mut connect));
+
+        let sender = std::net::UdpSocket::bind("127.0.0.1:0").expect("fail bind()");
+        sender.send_to(&[0; 2000], udp_addr).expect("fail send_to()");
+        for i in 0..7 {
+            sender.send_to(&[i; 188], udp_addr).expect("fail send_to()");
+        }
+        let mut buf = [0; 1500];
+        let len = peer.read(&mut buf).expect("fail read()");
+        assert_eq!(len, 1316);
+        assert!(buf[..len]
+            .chunks(188)
+            .enumerate()
+            .all(|(i, packet)| packet.iter().all(|&byte| byte == i as u8)));
+        assert_eq!(stats.datagrams(), 8);
+        assert_eq!(stats.oversized(), 1);
+
+        assert!(peer.close().is_ok());
+        assert!(listen.close().is_ok());
+        while !forward.is_finished() {
+            sender.send_to(&[0; 1316], udp_addr).expect("fail send_to()");
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(forward.join().expect("gateway thread panicked").is_err());
+    }
+    #[test]
+    fn test_multicast_v6_interface_rejected() {
+        use srt::gateway::UdpReceiver;
+
+        let group = SocketAddr::from(([0xff02, 0, 0, 0, 0, 0, 0, 0x114], 0));
+        let interface = Some(std::net::Ipv6Addr::LOCALHOST.into());
+        match UdpReceiver::join_multicast(group, interface) {
+            Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::InvalidInput),
+            Ok(_) => panic!("interface ignored for an IPv6 group"),
+        }
+    }
+    #[test]
+    fn test_relay_fan_out() {
+        use futures::{channel::oneshot, future::FutureExt};
+        use srt::{
+            error::{SrtErrorKind, SrtRejectReason},
+            relay::{self, Relay, RelayHooks, StreamId, StreamMode},
+        };
+
+        struct DenyIntruder;
+        impl RelayHooks for DenyIntruder {
+            fn authorize(&self, id: &StreamId, _peer: SocketAddr) -> Result<(), SrtRejectReason> {
+                match id.user.as_deref() {
+                    Some("intruder") => Err(relay::FORBIDDEN),
+                    _ => Ok(()),
+                }
+            }
+        }
+
+        let id: StreamId = "#!::r=live/cam1,m=publish,u=encoder,s=42"
+            .parse()
+            .expect("fail parse()");
+        assert_eq!(id.resource, "live/cam1");
+        assert_eq!(id.mode, StreamMode::Publish);
+        assert_eq!(id.user.as_deref(), Some("encoder"));
+        assert_eq!(id.other, vec![("s".to_string(), "42".to_string())]);
+        assert!("#!::m=watch".parse::<StreamId>().is_err());
+
+        let listen = srt::async_builder()
+            .listen("127.0.0.1:0", 4)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let relay = Relay::with_hooks(Default::default(), DenyIntruder);
+        let server = relay.clone();
+        let (stop, stopped) = oneshot::channel::<()>();
+        let server = thread::spawn(move || {
+            block_on(future::select(server.serve(&listen).boxed(), stopped));
+            listen
+        });
+
+        // Turned away during the handshake, with the reason the hook gave.
+        for (stream_id, reason) in &[
+            ("#!::r=live/cam1,u=intruder", relay::FORBIDDEN),
+            ("#!::m=watch", relay::BAD_REQUEST),
+            ("#!::r=live/cam1,m=bidirectional", relay::BAD_MODE),
+        ] {
+            match srt::builder()
+                .set_stream_id(stream_id.to_string())
+                .connect(addr)
+            {
+                Err(e) => assert_eq!(e.kind(), SrtErrorKind::ConnRej(*reason)),
+                Ok(_) => panic!("{} was not rejected", stream_id),
+            }
+        }
+
+        let subscribers: Vec<_> = (0..2)
+            .map(|_| {
+                srt::builder()
+                    .set_stream_id("#!::r=live/cam1".to_string())
+                    .connect(addr)
+                    .expect("fail connect()")
+            })
+            .collect();
+        let mut publisher = srt::builder()
+            .set_stream_id("#!::r=live/cam1,m=publish".to_string())
+            .connect(addr)
+            .expect("fail connect()");
+        while !relay
+            .stats()
+            .iter()
+            .any(|stats| stats.publishing && stats.subscribers == 2)
+        {
+            thread::sleep(Duration::from_millis(10));
+        }
+        match srt::builder()
+            .set_stream_id("#!::r=live/cam1,m=publish".to_string())
+            .connect(addr)
+        {
+            Err(e) => assert_eq!(e.kind(), SrtErrorKind::ConnRej(relay::CONFLICT)),
+            Ok(_) => panic!("second publisher was not rejected"),
+        }
+        publisher.write_all(b"payload").expect("fail write()");
+        for mut subscriber in subscribers {
+            let mut buf = [0; 1500];
+            let len = subscriber.read(&mut buf).expect("fail read()");
+            assert_eq!(&buf[..len], b"payload");
+            assert!(subscriber.close().is_ok());
+        }
+        assert!(publisher.close().is_ok());
+
+        stop.send(()).expect("fail send()");
+        let listen = server.join().expect("server thread panicked");
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_listen_all() {
+        let listen = srt::builder()
+            .listen_all(&[SocketAddr::from(([127, 0, 0, 1], 0)); 2][..], 2)
+            .expect("fail listen_all()");
+        let addrs = listen.local_addrs().expect("fail local_addrs()");
+        assert_eq!(addrs.len(), 2);
+        let connect = thread::spawn(move || {
+            srt::builder().connect(addrs[1]).expect("fail connect()")
+        });
+        let (peer, _peer_addr) = listen.accept().expect("fail accept()");
+        let connect = connect.join().expect("connect thread panicked");
+        assert_eq!(
+            peer.local_addr().expect("fail local_addr()"),
+            listen.listeners()[1].local_addr().expect("fail local_addr()")
+        );
+
+        let port = listen.local_addrs().expect("fail local_addrs()")[0].port();
+        match srt::builder().listen_all(("127.0.0.1", port), 1) {
+            Err(srt::BindError::Failed(failures)) => assert_eq!(failures.len(), 1),
+            _ => panic!("expected the port to be taken"),
+        }
+        assert!(connect.close().is_ok());
+        assert!(peer.close().is_ok());
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_listen_dual_stack_same_port() {
+        let listen = srt::builder()
+            .listen_dual_stack(0, 1)
+            .expect("fail listen_dual_stack()");
+        let addrs = listen.local_addrs().expect("fail local_addrs()");
+        assert_eq!(addrs.len(), 2);
+        assert!(addrs[0].is_ipv4() && addrs[1].is_ipv6());
+        assert_ne!(addrs[0].port(), 0);
+        assert_eq!(addrs[0].port(), addrs[1].port());
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_bind_acquire() {
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").expect("fail bind()");
+        let addr = udp.local_addr().expect("fail local_addr()");
+        let listen = srt::builder()
+            .bind_acquire(udp)
+            .expect("fail bind_acquire()")
+            .listen(1)
+            .expect("fail listen()");
+        assert_eq!(listen.local_addr().expect("fail local_addr()"), addr);
+        let accept = thread::spawn(move || {
+            let (mut peer, _peer_addr) = listen.accept().expect("fail accept()");
+            peer.write_all(b"acquired").expect("fail write()");
+            (listen, peer)
+        });
+        let mut connect = srt::builder().connect(addr).expect("fail connect()");
+        let mut buf = [0; 16];
+        let len = connect.read(&mut buf).expect("fail read()");
+        assert_eq!(&buf[..len], b"acquired");
+        let (listen, peer) = accept.join().expect("accept thread panicked");
+        assert!(connect.close().is_ok());
+        assert!(peer.close().is_ok());
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_bind_acquire_async() {
+        let udp = std::net::UdpSocket::bind("127.0.0.1:0").expect("fail bind()");
+        let addr = udp.local_addr().expect("fail local_addr()");
+        let listen = srt::async_builder()
+            .bind_acquire(udp)
+            .expect("fail bind_acquire()")
+            .listen(1)
+            .expect("fail listen()");
+        let connect = srt::async_builder().connect(addr).expect("fail connect()");
+        let (accepted, connected) = block_on(future::join(listen.accept(), connect));
+        let (mut peer, _peer_addr) = accepted.expect("fail accept()");
+        let mut connect = connected.expect("fail connect()");
+        assert_eq!(connect.peer_addr().expect("fail peer_addr()"), addr);
+        assert!(block_on(connect.close()).is_ok());
+        assert!(block_on(peer.close()).is_ok());
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    #[cfg(target_family = "unix")]
+    fn test_systemd_listen_fds() {
+        use srt::systemd::{ListenFds, Match};
+        use std::{
+            collections::HashMap,
+            os::unix::{
+                io::{FromRawFd, IntoRawFd},
+                net::UnixDatagram,
+            },
+            process,
+        };
+
+        let env: HashMap<_, _> = vec![
+            ("LISTEN_PID", process::id().to_string()),
+            ("LISTEN_FDS", "2".to_string()),
+            ("LISTEN_FDNAMES", "srt:".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let vars = |key: &str| env.get(key).cloned();
+        let names = srt::systemd::parse_env(&vars, process::id()).expect("fail parse_env()");
+        assert_eq!(names, vec![Some("srt".to_string()), None]);
+        assert!(srt::systemd::parse_env(&vars, process::id() + 1)
+            .expect("fail parse_env()")
+            .is_empty());
+
+        let named = std::net::UdpSocket::bind("127.0.0.1:0").expect("fail bind()");
+        let unnamed = std::net::UdpSocket::bind("127.0.0.1:0").expect("fail bind()");
+        let named_addr = named.local_addr().expect("fail local_addr()");
+        let unnamed_addr = unnamed.local_addr().expect("fail local_addr()");
+        // A datagram socket, but not a UDP one.
+        let unix = UnixDatagram::unbound()
+            .expect("fail unbound()")
+            .into_raw_fd();
+        let fds = vec![named.into_raw_fd(), unnamed.into_raw_fd(), unix];
+        let names = names.into_iter().chain(Some(Some("unix".to_string())));
+        let mut listen_fds = unsafe { ListenFds::from_fds(fds.into_iter().zip(names)) };
+        assert!(listen_fds.take(Match::Name("other")).is_none());
+        assert!(listen_fds.take(Match::Name("unix")).is_none());
+        drop(unsafe { UnixDatagram::from_raw_fd(unix) });
+        let listen = listen_fds
+            .listen(Match::Name("srt"), srt::builder(), 1)
+            .expect("fail listen()");
+        assert_eq!(listen.local_addr().expect("fail local_addr()"), named_addr);
+        let async_listen = listen_fds
+            .listen_async(Match::Addr(unnamed_addr), srt::async_builder(), 1)
+            .expect("fail listen_async()");
+        assert!(listen_fds.is_empty());
+        let connect = srt::async_builder()
+            .connect(unnamed_addr)
+            .expect("fail connect()");
+        let (accepted, connected) = block_on(future::join(async_listen.accept(), connect));
+        let (mut peer, _peer_addr) = accepted.expect("fail accept()");
+        let mut connect = connected.expect("fail connect()");
+        assert!(block_on(connect.close()).is_ok());
+        assert!(block_on(peer.close()).is_ok());
+        assert!(async_listen.close().is_ok());
+        assert!(listen.close().is_ok());
+    }
+    #[test]
+    fn test_graceful_shutdown() {
+        srt::startup().expect("failed startup");
+        let (tx, rx) = mpsc::channel::<SocketAddr>();
+        let listen_thread = thread::spawn(move || {
+            let listen = srt::builder()
+                .set_file_transmission_type()
+                .listen("127.0.0.1:0", 1)
+                .expect("fail listen()");
+            let local = listen.local_addr().expect("fail local_addr()");
+            tx.send(local).expect("fail send through mpsc channel");
+            let (mut peer, _peer_addr) = listen.accept().expect("fail accep()");
+            peer.write_all(&[7; 64 * 1024]).expect("fail write()");
+            peer.shutdown(Some(Duration::from_secs(5)))
+                .expect("fail shutdown()");
+            listen
+                .shutdown(Some(Duration::from_secs(5)))
+                .expect("fail listener shutdown()");
+        });
+        let addr = rx.recv().expect("fail recv through mpsc channel");
+        let mut connect = srt::builder()
+            .set_file_transmission_type()
+            .connect(addr)
+            .expect("fail connect()");
+        let mut buf = Vec::new();
+        connect.read_to_end(&mut buf).expect("fail read()");
+        assert_eq!(buf, vec![7; 64 * 1024]);
+        assert!(connect.close().is_ok());
+        listen_thread.join().expect("listener thread panicked");
+        srt::cleanup().expect("failed cleanup()");
+    }
+    #[test]
+    fn test_listener_shutdown_timeout() {
+        use srt::error::SrtErrorKind;
+        use std::time::Instant;
+
+        let timeout = Duration::from_millis(200);
+        let listen = srt::builder()
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let connect = thread::spawn(move || {
+            srt::builder().connect(addr).expect("fail connect()")
+        });
+        let (peer, _peer_addr) = listen.accept().expect("fail accept()");
+        let mut connect = connect.join().expect("connect thread panicked");
+        connect
+            .set_receive_timeout(2000)
+            .expect("fail set_receive_timeout()");
+        // The accepted connection stays open past the timeout.
+        let started = Instant::now();
+        assert_eq!(
+            listen.shutdown(Some(timeout)).map_err(|e| e.kind()),
+            Err(SrtErrorKind::Timeout)
+        );
+        assert!(started.elapsed() >= timeout);
+        assert!(!matches!(
+            peer.handle().get_socket_state(),
+            Ok(srt::SrtSocketStatus::Connected)
+        ));
+        let mut buf = [0; 16];
+        assert!(!matches!(connect.read(&mut buf), Ok(len) if len > 0));
+        drop(peer);
+        assert!(connect.close().is_ok());
+
+        let listen = srt::async_builder()
+            .listen("127.0.0.1:0", 1)
+            .expect("fail listen()");
+        let addr = listen.local_addr().expect("fail local_addr()");
+        let connect = srt::async_builder().connect(addr).expect("fail connect()");
+        let (accepted, connected) = block_on(future::join(listen.accept(), connect));
+        let (peer, _peer_addr) = accepted.expect("fail accept()");
+        let mut connect = connected.expect("fail connect()");
+        let started = Instant::now();
+        let shutdown = listen
+            .shutdown(Some(timeout))
+            .expect("fail start listener shutdown");
+        assert_eq!(
+            block_on(shutdown).map_err(|e| e.kind()),
+            Err(SrtErrorKind::Timeout)
+        );
+        assert!(started.elapsed() >= timeout);
+        assert!(!matches!(
+            peer.handle().get_socket_state(),
+            Ok(srt::SrtSocketStatus::Connected)
+        ));
+        drop(peer);
+        assert!(block_on(connect.close()).is_ok());
+    }
+    #[test]
+    fn test_graceful_shutdown_async() {
+        srt::startup().expect("failed startup");
+        let (tx, rx) = mpsc::channel::<SocketAddr>();
+        let listen_task = async move {
+            let listen = srt::async_builder()
+                .set_file_transmission_type()
+                .listen("127.0.0.1:0", 1)
+                .expect("fail listen()");
+            let local = listen.local_addr().expect("fail local_addr()");
+            tx.send(local).expect("fail send through mpsc channel");
+            let (mut peer, _peer_addr) = listen.accept().await.expect("fail accep()");
+            peer.write_all(&[7; 64 * 1024]).await.expect("fail write()");
+            peer.shutdown(Some(Duration::from_secs(5)))
+                .await
+                .expect("fail shutdown()");
+            listen
+                .shutdown(Some(Duration::from_secs(5)))
+                .expect("fail start listener shutdown")
+                .await
+                .expect("fail listener shutdown");
+        };
+        let connect_task = async move {
+            let addr = rx.recv().expect("fail recv through mpsc channel");
+            let mut connect = srt::async_builder()
+                .set_file_transmission_type()
+                .connect(addr)
+                .expect("fail start connect")
+                .await
+                .expect("fail connect");
+            let mut buf = Vec::new();
+            connect.read_to_end(&mut buf).await.expect("fail read()");
+            assert_eq!(buf, vec![7; 64 * 1024]);
+            assert!(connect.close().await.is_ok());
+        };
+        block_on(future::join(listen_task, connect_task));
+        srt::cleanup().expect("failed cleanup()");
+    }
+
     #[test]
     fn test_ipv4_manual_rendezvous() {
         srt::startup().expect("failed startup");
@@ -0,0 +1,92 @@
+//! Routes libsrt's listen hook to Rust closures. libsrt calls the hook of a
+//! listener for every caller, before answering its handshake, so a closure
+//! can turn the caller away with a reject reason it will see.
+
+use crate::{
+    error::{self, SrtError, SrtRejectReason},
+    socket::SrtSocket,
+};
+
+use libsrt_sys as srt;
+use os_socketaddr::OsSocketAddr;
+
+use std::{
+    ffi::CStr,
+    net::SocketAddr,
+    os::raw::{c_char, c_int, c_void},
+    panic::{self, AssertUnwindSafe},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+/// Gets the caller's stream ID and address.
+pub(crate) type ListenCallback =
+    Arc<dyn Fn(&str, Option<SocketAddr>) -> std::result::Result<(), SrtRejectReason> + Send + Sync>;
+
+// libsrt only lets applications set codes from here on; it reports this one
+// for a rejection without a usable code.
+const REJC_PREDEFINED: u32 = 1000;
+
+static CALLBACKS: Mutex<Vec<(i32, ListenCallback)>> = Mutex::new(Vec::new());
+
+fn callbacks_lock() -> MutexGuard<'static, Vec<(i32, ListenCallback)>> {
+    CALLBACKS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Replaces the hook of `listener` with `callback`. Rejections other than
+/// `SrtRejectReason::Other(code)` with `code` of 1000 and up are reported
+/// to the caller as 1000.
+pub(crate) fn set(listener: &SrtSocket, callback: ListenCallback) -> Result<()> {
+    {
+        let mut callbacks = callbacks_lock();
+        callbacks.retain(|(id, _)| *id != listener.id);
+        callbacks.push((listener.id, callback));
+    }
+    let result = unsafe {
+        srt::srt_listen_callback(
+            listener.id,
+            Some(on_listen),
+            listener.id as isize as *mut c_void,
+        )
+    };
+    // Registered first so no caller slips in unchecked; undone on failure.
+    error::handle_result((), result).inspect_err(|_| remove(listener))
+}
+
+pub(crate) fn remove(listener: &SrtSocket) {
+    callbacks_lock().retain(|(id, _)| *id != listener.id);
+}
+
+// The opaque pointer is the ID of the listener, as for the connect hook.
+unsafe extern "C" fn on_listen(
+    opaque: *mut c_void,
+    ns: srt::SRTSOCKET,
+    _hsversion: c_int,
+    peeraddr: *const srt::sockaddr,
+    streamid: *const c_char,
+) -> c_int {
+    let id = opaque as isize as i32;
+    let callback = match callbacks_lock()
+        .iter()
+        .find(|(registered, _)| *registered == id)
+    {
+        Some((_, callback)) => callback.clone(),
+        None => return 0,
+    };
+    let stream_id = if streamid.is_null() {
+        Default::default()
+    } else {
+        CStr::from_ptr(streamid).to_string_lossy()
+    };
+    let peer = OsSocketAddr::copy_from_raw(peeraddr as *const _, OsSocketAddr::new().capacity())
+        .into_addr();
+    // Unwinding into libsrt is undefined behaviour.
+    let reason = match panic::catch_unwind(AssertUnwindSafe(|| callback(&stream_id, peer))) {
+        Ok(Ok(())) => return 0,
+        Ok(Err(SrtRejectReason::Other(code))) if code >= REJC_PREDEFINED => code,
+        _ => REJC_PREDEFINED,
+    };
+    srt::srt_setrejectreason(ns, reason as c_int);
+    -1
+}
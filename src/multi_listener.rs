@@ -0,0 +1,203 @@
+use crate::{
+    async_stream,
+    error::{SrtError, SrtErrorKind},
+    socket::SrtSocket,
+    Epoll, SrtAsyncListener, SrtAsyncStream, SrtListener, SrtStream,
+};
+
+use futures::{
+    future::Future,
+    task::{Context, Poll},
+};
+use libsrt_sys as srt;
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
+    pin::Pin,
+    thread,
+};
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+#[derive(Debug)]
+pub enum BindError {
+    /// The addresses did not resolve to anything.
+    NoAddress,
+    /// Every address that could not be bound, with the reason. Addresses
+    /// that were bound have been closed again.
+    Failed(Vec<(SocketAddr, SrtError)>),
+}
+
+impl Display for BindError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            BindError::NoAddress => write!(f, "no address to listen on"),
+            BindError::Failed(failures) => {
+                write!(f, "cannot listen on")?;
+                for (i, (addr, e)) in failures.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { ", " };
+                    write!(f, "{}{} ({})", separator, addr, e)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for BindError {}
+
+/// The IPv4 and IPv6 wildcard addresses for `port`.
+/// Listens on `port` of both wildcard addresses. With port 0 the IPv6
+/// listener takes the port the IPv4 one was given.
+pub(crate) fn listen_dual_stack<L>(
+    port: u16,
+    mut listen: impl FnMut(SocketAddr) -> Result<L>,
+    local_addr: impl Fn(&L) -> Result<SocketAddr>,
+) -> std::result::Result<Vec<L>, BindError> {
+    let v4 = SocketAddr::from((Ipv4Addr::UNSPECIFIED, port));
+    let failed = |addr, e| BindError::Failed(vec![(addr, e)]);
+    let v4_listener = listen(v4).map_err(|e| failed(v4, e))?;
+    let port = local_addr(&v4_listener).map_err(|e| failed(v4, e))?.port();
+    let v6 = SocketAddr::from((Ipv6Addr::UNSPECIFIED, port));
+    // Dropping `v4_listener` closes it again.
+    let v6_listener = listen(v6).map_err(|e| failed(v6, e))?;
+    Ok(vec![v4_listener, v6_listener])
+}
+
+/// Listens on every address `addrs` resolves to, all or nothing.
+pub(crate) fn listen_all<A: ToSocketAddrs, L>(
+    addrs: A,
+    mut listen: impl FnMut(SocketAddr) -> Result<L>,
+) -> std::result::Result<Vec<L>, BindError> {
+    let addrs: Vec<_> = match addrs.to_socket_addrs() {
+        Ok(addrs) => addrs.collect(),
+        Err(_) => return Err(BindError::NoAddress),
+    };
+    if addrs.is_empty() {
+        return Err(BindError::NoAddress);
+    }
+    let mut listeners = Vec::new();
+    let mut failures = Vec::new();
+    for addr in addrs {
+        match listen(addr) {
+            Ok(listener) => listeners.push(listener),
+            Err(e) => failures.push((addr, e)),
+        }
+    }
+    if failures.is_empty() {
+        Ok(listeners)
+    } else {
+        Err(BindError::Failed(failures))
+    }
+}
+
+/// Several listeners whose connections are accepted through one call.
+pub struct SrtMultiListener {
+    listeners: Vec<SrtListener>,
+}
+
+impl SrtMultiListener {
+    pub(crate) fn new(listeners: Vec<SrtListener>) -> Self {
+        Self { listeners }
+    }
+    /// Blocks until any of the listeners has a connection.
+    pub fn accept(&self) -> Result<(SrtStream, SocketAddr)> {
+        let mut epoll = Epoll::new()?;
+        for listener in &self.listeners {
+            epoll.add(&listener.socket, &srt::SRT_EPOLL_OPT::SRT_EPOLL_IN)?;
+        }
+        let ready = epoll.wait(-1)?;
+        let listener = ready
+            .iter()
+            .find_map(|(socket, _)| {
+                self.listeners
+                    .iter()
+                    .find(|listener| listener.socket.id == socket.id)
+            })
+            .ok_or(SrtError::from(SrtErrorKind::PollEmpty))?;
+        listener.accept()
+    }
+    pub fn listeners(&self) -> &[SrtListener] {
+        &self.listeners
+    }
+    pub fn local_addrs(&self) -> Result<Vec<SocketAddr>> {
+        self.listeners
+            .iter()
+            .map(|listener| listener.local_addr())
+            .collect()
+    }
+    /// Closes every listener; returns the first error met.
+    pub fn close(self) -> Result<()> {
+        let mut result = Ok(());
+        for listener in self.listeners {
+            result = result.and(listener.close());
+        }
+        result
+    }
+}
+
+pub struct SrtAsyncMultiListener {
+    listeners: Vec<SrtAsyncListener>,
+}
+
+impl SrtAsyncMultiListener {
+    pub(crate) fn new(listeners: Vec<SrtAsyncListener>) -> Self {
+        Self { listeners }
+    }
+    pub fn accept(&self) -> MultiAcceptFuture {
+        MultiAcceptFuture {
+            sockets: self
+                .listeners
+                .iter()
+                .map(|listener| listener.socket)
+                .collect(),
+        }
+    }
+    pub fn listeners(&self) -> &[SrtAsyncListener] {
+        &self.listeners
+    }
+    pub fn local_addrs(&self) -> Result<Vec<SocketAddr>> {
+        self.listeners
+            .iter()
+            .map(|listener| listener.local_addr())
+            .collect()
+    }
+    /// Closes every listener; returns the first error met.
+    pub fn close(self) -> Result<()> {
+        let mut result = Ok(());
+        for listener in self.listeners {
+            result = result.and(listener.close());
+        }
+        result
+    }
+}
+
+pub struct MultiAcceptFuture {
+    sockets: Vec<SrtSocket>,
+}
+
+impl Future for MultiAcceptFuture {
+    type Output = Result<(SrtAsyncStream, SocketAddr)>;
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        for socket in &self.sockets {
+            match socket.accept() {
+                Ok((socket, addr)) => return Poll::Ready(async_stream(socket).map(|s| (s, addr))),
+                Err(e) if e.kind() == SrtErrorKind::AsyncRcv => {}
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+        let mut epoll = Epoll::new()?;
+        for socket in &self.sockets {
+            epoll.add(socket, &srt::SRT_EPOLL_OPT::SRT_EPOLL_IN)?;
+        }
+        let waker = cx.waker().clone();
+        thread::spawn(move || {
+            if epoll.wait(-1).is_ok() {
+                waker.wake();
+            }
+        });
+        Poll::Pending
+    }
+}
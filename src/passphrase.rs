@@ -0,0 +1,160 @@
+use std::{
+    env,
+    error::Error,
+    ffi::OsString,
+    fmt::{self, Debug, Display, Formatter},
+    fs::File,
+    io::{self, ErrorKind, Read},
+    path::Path,
+    ptr,
+    sync::{
+        atomic::{self, Ordering},
+        Arc,
+    },
+};
+
+// Length limits libsrt enforces on SRTO_PASSPHRASE.
+const MIN_LEN: usize = 10;
+const MAX_LEN: usize = 79;
+
+/// An SRT encryption passphrase. Clones share one copy of the secret, which is
+/// overwritten with zeroes once the last clone is dropped.
+#[derive(Clone)]
+pub struct Passphrase(Arc<Secret>);
+
+struct Secret(String);
+
+impl Passphrase {
+    pub fn new(passphrase: impl Into<String>) -> Result<Self, PassphraseError> {
+        let secret = Secret(passphrase.into());
+        match secret.0.len() {
+            MIN_LEN..=MAX_LEN => Ok(Self(Arc::new(secret))),
+            len => Err(PassphraseError::Length(len)),
+        }
+    }
+    pub fn from_env(key: &str) -> Result<Self, PassphraseError> {
+        let passphrase = env::var_os(key).ok_or(PassphraseError::NotPresent)?;
+        match passphrase.into_string() {
+            Ok(passphrase) => Self::new(passphrase),
+            Err(passphrase) => {
+                wipe_os_string(passphrase);
+                Err(PassphraseError::NotUnicode)
+            }
+        }
+    }
+    /// Reads the passphrase from a file, ignoring trailing line breaks.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, PassphraseError> {
+        let mut file = File::open(path).map_err(PassphraseError::Io)?;
+        // Room for the longest passphrase, "\r\n" and a byte that gives away
+        // a longer file. A fixed buffer is never reallocated, which would
+        // leave copies behind.
+        let mut buf = Wiped([0; MAX_LEN + 3]);
+        let mut len = 0;
+        while len < buf.0.len() {
+            match file.read(&mut buf.0[len..]) {
+                Ok(0) => break,
+                Ok(read) => len += read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(PassphraseError::Io(e)),
+            }
+        }
+        if len == buf.0.len() {
+            let len = file
+                .metadata()
+                .map_or(len as u64, |metadata| metadata.len());
+            return Err(PassphraseError::Length(len as usize));
+        }
+        let passphrase = std::str::from_utf8(&buf.0[..len])
+            .map_err(|_| PassphraseError::Io(ErrorKind::InvalidData.into()))?;
+        Self::new(passphrase.trim_end_matches(&['\r', '\n'][..]))
+    }
+    pub(crate) fn as_str(&self) -> &str {
+        &(self.0).0
+    }
+}
+
+impl Debug for Passphrase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Passphrase(<redacted>)")
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        wipe_vec(unsafe { self.0.as_mut_vec() });
+    }
+}
+
+// Zeroes a buffer that held a secret when it is dropped.
+struct Wiped<T: AsMut<[u8]>>(T);
+
+impl<T: AsMut<[u8]>> Drop for Wiped<T> {
+    fn drop(&mut self) {
+        let bytes = self.0.as_mut();
+        unsafe { wipe(bytes.as_mut_ptr(), bytes.len()) };
+    }
+}
+
+// Volatile writes so the wipe is not optimized away as a dead store. `ptr`
+// has to be valid for `len` bytes.
+unsafe fn wipe(ptr: *mut u8, len: usize) {
+    for i in 0..len {
+        ptr::write_volatile(ptr.add(i), 0);
+    }
+    atomic::compiler_fence(Ordering::SeqCst);
+}
+
+// Covers the whole capacity, including bytes left behind by `truncate`.
+fn wipe_vec(bytes: &mut Vec<u8>) {
+    let capacity = bytes.capacity();
+    bytes.clear();
+    unsafe { wipe(bytes.as_mut_ptr(), capacity) };
+}
+
+#[cfg(unix)]
+fn wipe_os_string(value: OsString) {
+    use std::os::unix::ffi::OsStringExt;
+
+    wipe_vec(&mut value.into_vec());
+}
+
+// Other platforms give no access to the bytes of an `OsString`.
+#[cfg(not(unix))]
+fn wipe_os_string(_value: OsString) {}
+
+#[derive(Debug)]
+pub enum PassphraseError {
+    Length(usize),
+    /// The environment variable is not set.
+    NotPresent,
+    /// The environment variable is not valid Unicode; its value is left out.
+    NotUnicode,
+    /// Reading the file failed, or it is not valid UTF-8.
+    Io(io::Error),
+}
+
+impl Display for PassphraseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PassphraseError::Length(len) => write!(
+                f,
+                "passphrase must be {} to {} bytes long, got {}",
+                MIN_LEN, MAX_LEN, len
+            ),
+            PassphraseError::NotPresent => f.write_str("passphrase variable is not set"),
+            PassphraseError::NotUnicode => f.write_str("passphrase variable is not valid unicode"),
+            PassphraseError::Io(e) => write!(f, "cannot read passphrase file: {}", e),
+        }
+    }
+}
+
+impl Error for PassphraseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PassphraseError::Length(_)
+            | PassphraseError::NotPresent
+            | PassphraseError::NotUnicode => None,
+            PassphraseError::Io(e) => Some(e),
+        }
+    }
+}
@@ -0,0 +1,369 @@
+use crate::{
+    error::{SrtError, SrtErrorKind},
+    wake_after, ConnectFuture, SrtAsyncBuilder, SrtAsyncStream, SrtPreConnectOpt, SrtSocketStatus,
+};
+
+use futures::{
+    channel::mpsc,
+    future::Future,
+    io::{AsyncRead, AsyncWrite},
+    task::{Context, Poll},
+};
+
+use std::{
+    collections::VecDeque,
+    io::{self, ErrorKind},
+    net::{SocketAddr, ToSocketAddrs},
+    pin::Pin,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+/// What writes do while there is no connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WritePolicy {
+    /// Writes wait for the connection to come back.
+    Wait,
+    /// Writes succeed without sending anything.
+    Drop,
+    /// Up to this many bytes are kept and sent once reconnected, the oldest
+    /// writes being dropped first.
+    Buffer(usize),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReconnectConfig {
+    /// Wait after the first failed attempt.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Factor the wait grows by with every further failed attempt.
+    pub multiplier: f64,
+    /// Every wait is randomly shortened or stretched by up to this fraction,
+    /// between 0 and 1.
+    pub jitter: f64,
+    /// Failed attempts in a row before giving up, `None` to retry forever.
+    pub max_attempts: Option<u32>,
+    pub write_policy: WritePolicy,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: 0.1,
+            max_attempts: None,
+            write_policy: WritePolicy::Wait,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum ReconnectEvent {
+    Connected,
+    Disconnected(SrtError),
+    AttemptFailed {
+        attempt: u32,
+        error: SrtError,
+        retry_in: Duration,
+    },
+    /// `max_attempts` was reached; the stream stays disconnected.
+    GaveUp,
+}
+
+enum State {
+    Connected(SrtAsyncStream),
+    Connecting(ConnectFuture),
+    Waiting(Instant),
+    Stopped,
+}
+
+/// A caller that connects again, with the same options, whenever its
+/// connection is lost. Reads wait while reconnecting; writes follow the
+/// configured `WritePolicy`.
+pub struct ReconnectingStream {
+    opt_vec: Vec<SrtPreConnectOpt>,
+    remote: SocketAddr,
+    config: ReconnectConfig,
+    state: State,
+    failed_attempts: u32,
+    backlog: VecDeque<Vec<u8>>,
+    backlog_bytes: usize,
+    dropped_writes: u64,
+    events: Vec<mpsc::UnboundedSender<ReconnectEvent>>,
+    rng: u64,
+}
+
+impl ReconnectingStream {
+    /// Starts connecting to `remote`; no I/O is needed to get connected.
+    pub fn new<A: ToSocketAddrs>(
+        builder: SrtAsyncBuilder,
+        remote: A,
+        config: ReconnectConfig,
+    ) -> Result<Self> {
+        let remote = remote
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addrs| addrs.next())
+            .ok_or(SrtError::from(SrtErrorKind::SockFail))?;
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |now| now.as_nanos() as u64);
+        let mut stream = Self {
+            opt_vec: builder.opt_vec,
+            remote,
+            config,
+            state: State::Stopped,
+            failed_attempts: 0,
+            backlog: VecDeque::new(),
+            backlog_bytes: 0,
+            dropped_writes: 0,
+            events: Vec::new(),
+            rng: seed | 1,
+        };
+        stream.start_connect();
+        Ok(stream)
+    }
+    /// State changes from now on. Events are dropped for receivers that are
+    /// gone.
+    pub fn events(&mut self) -> mpsc::UnboundedReceiver<ReconnectEvent> {
+        let (tx, rx) = mpsc::unbounded();
+        self.events.push(tx);
+        rx
+    }
+    pub fn is_connected(&self) -> bool {
+        matches!(self.state, State::Connected(_))
+    }
+    /// The current connection, if any.
+    pub fn stream(&self) -> Option<&SrtAsyncStream> {
+        match &self.state {
+            State::Connected(stream) => Some(stream),
+            _ => None,
+        }
+    }
+    /// Writes dropped, or evicted from the buffer, while disconnected.
+    pub fn dropped_writes(&self) -> u64 {
+        self.dropped_writes
+    }
+
+    fn emit(&mut self, event: ReconnectEvent) {
+        self.events
+            .retain(|tx| tx.unbounded_send(event.clone()).is_ok());
+    }
+    fn start_connect(&mut self) {
+        let builder = SrtAsyncBuilder {
+            opt_vec: self.opt_vec.clone(),
+        };
+        match builder.connect(self.remote) {
+            Ok(future) => self.state = State::Connecting(future),
+            Err(e) => self.attempt_failed(e),
+        }
+    }
+    fn attempt_failed(&mut self, error: SrtError) {
+        self.failed_attempts += 1;
+        if matches!(self.config.max_attempts, Some(max) if self.failed_attempts >= max) {
+            self.state = State::Stopped;
+            self.emit(ReconnectEvent::GaveUp);
+            return;
+        }
+        let retry_in = self.backoff();
+        self.state = State::Waiting(Instant::now() + retry_in);
+        self.emit(ReconnectEvent::AttemptFailed {
+            attempt: self.failed_attempts,
+            error,
+            retry_in,
+        });
+    }
+    fn backoff(&mut self) -> Duration {
+        let config = &self.config;
+        let exponent = self.failed_attempts.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let backoff = config.initial_backoff.as_secs_f64() * config.multiplier.powi(exponent);
+        let backoff = backoff.min(config.max_backoff.as_secs_f64());
+        // xorshift64, plenty to spread out reconnecting callers.
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        let random = (self.rng >> 11) as f64 / (1u64 << 53) as f64;
+        let jitter = config.jitter.clamp(0.0, 1.0) * (2.0 * random - 1.0);
+        Duration::from_secs_f64(backoff * (1.0 + jitter))
+    }
+    fn disconnected(&mut self, error: SrtError) {
+        self.state = State::Stopped;
+        self.failed_attempts = 0;
+        self.emit(ReconnectEvent::Disconnected(error));
+        self.start_connect();
+    }
+    // Ready once connected; an error means the stream was closed or gave up.
+    fn poll_connected(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            match &mut self.state {
+                State::Connected(_) => return Poll::Ready(Ok(())),
+                State::Connecting(future) => match Pin::new(future).poll(cx) {
+                    Poll::Ready(Ok(stream)) => {
+                        self.failed_attempts = 0;
+                        self.state = State::Connected(stream);
+                        self.emit(ReconnectEvent::Connected);
+                    }
+                    Poll::Ready(Err(e)) => self.attempt_failed(e),
+                    Poll::Pending => return Poll::Pending,
+                },
+                State::Waiting(retry_at) => {
+                    let now = Instant::now();
+                    if now < *retry_at {
+                        wake_after(cx, *retry_at - now);
+                        return Poll::Pending;
+                    }
+                    self.start_connect();
+                }
+                State::Stopped => return Poll::Ready(Err(ErrorKind::NotConnected.into())),
+            }
+        }
+    }
+    // Sends what was buffered while disconnected, oldest first.
+    fn poll_backlog(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let stream = match &mut self.state {
+            State::Connected(stream) => stream,
+            _ => return Poll::Ready(Ok(())),
+        };
+        while let Some(payload) = self.backlog.front() {
+            match Pin::new(&mut *stream).poll_write(cx, payload) {
+                Poll::Ready(Ok(_)) => {
+                    self.backlog_bytes -= payload.len();
+                    self.backlog.pop_front();
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+    fn write_disconnected(&mut self, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.config.write_policy {
+            WritePolicy::Wait => return Poll::Pending,
+            WritePolicy::Drop => self.dropped_writes += 1,
+            WritePolicy::Buffer(max_bytes) => {
+                while !self.backlog.is_empty() && self.backlog_bytes + buf.len() > max_bytes {
+                    if let Some(oldest) = self.backlog.pop_front() {
+                        self.backlog_bytes -= oldest.len();
+                        self.dropped_writes += 1;
+                    }
+                }
+                if buf.len() > max_bytes {
+                    self.dropped_writes += 1;
+                } else {
+                    self.backlog.push_back(buf.to_vec());
+                    self.backlog_bytes += buf.len();
+                }
+            }
+        }
+        Poll::Ready(Ok(buf.len()))
+    }
+    // The lost connection, if `e` means it is gone rather than that the call
+    // was wrong.
+    fn lost(&self, e: &io::Error) -> Option<SrtError> {
+        if let Some(e) = e.get_ref().and_then(|e| e.downcast_ref::<SrtError>()) {
+            if matches!(e.kind(), SrtErrorKind::ConnLost | SrtErrorKind::NoConn) {
+                return Some(e.clone());
+            }
+        }
+        let stream = self.stream()?;
+        match stream.socket.get_socket_state() {
+            Ok(SrtSocketStatus::Broken) => Some(SrtErrorKind::ConnLost.into()),
+            _ => None,
+        }
+    }
+}
+
+impl AsyncRead for ReconnectingStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            match this.poll_connected(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+            let stream = match &mut this.state {
+                State::Connected(stream) => stream,
+                _ => continue,
+            };
+            match Pin::new(stream).poll_read(cx, buf) {
+                // The peer closed the connection.
+                Poll::Ready(Ok(0)) if !buf.is_empty() => {
+                    this.disconnected(SrtErrorKind::ConnLost.into())
+                }
+                Poll::Ready(Err(e)) => match this.lost(&e) {
+                    Some(lost) => this.disconnected(lost),
+                    None => return Poll::Ready(Err(e)),
+                },
+                other => return other,
+            }
+        }
+    }
+}
+
+impl AsyncWrite for ReconnectingStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            match this.poll_connected(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return this.write_disconnected(buf),
+            }
+            let written = match this.poll_backlog(cx) {
+                Poll::Ready(Ok(())) => match &mut this.state {
+                    State::Connected(stream) => Pin::new(stream).poll_write(cx, buf),
+                    _ => continue,
+                },
+                Poll::Ready(Err(e)) => Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            };
+            match written {
+                Poll::Ready(Err(e)) => match this.lost(&e) {
+                    Some(lost) => this.disconnected(lost),
+                    None => return Poll::Ready(Err(e)),
+                },
+                other => return other,
+            }
+        }
+    }
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        match &mut this.state {
+            State::Connected(stream) => match Pin::new(stream).poll_flush(cx) {
+                Poll::Ready(Err(e)) => match this.lost(&e) {
+                    Some(lost) => {
+                        this.disconnected(lost);
+                        Poll::Ready(Ok(()))
+                    }
+                    None => Poll::Ready(Err(e)),
+                },
+                other => other,
+            },
+            _ => Poll::Ready(Ok(())),
+        }
+    }
+    /// Closes the current connection and stops reconnecting.
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let closed = match &mut this.state {
+            State::Connected(stream) => Pin::new(stream).poll_close(cx),
+            _ => Poll::Ready(Ok(())),
+        };
+        if closed.is_ready() {
+            this.state = State::Stopped;
+        }
+        closed
+    }
+}
@@ -0,0 +1,430 @@
+//! A publish/subscribe hub: callers whose stream ID says `m=publish` feed a
+//! resource, callers with `m=request` (the default) receive it.
+//!
+//! Stream IDs follow the SRT access control syntax,
+//! `#!::r=<resource>,m=publish,u=<user>`; an ID without the `#!::` prefix is
+//! taken as a plain resource name.
+
+use crate::{
+    error::SrtRejectReason, listen_callback, socket::SrtSocket, SrtAsyncListener, SrtAsyncStream,
+};
+
+use futures::{
+    future::{self, FutureExt},
+    io::{AsyncReadExt, AsyncWriteExt},
+    select,
+    stream::{FuturesUnordered, StreamExt},
+};
+
+use std::{
+    collections::{HashMap, VecDeque},
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io,
+    net::SocketAddr,
+    str::FromStr,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    task::{Poll, Waker},
+};
+
+// Larger than any live-mode payload.
+const MAX_PAYLOAD_SIZE: usize = 1500;
+
+/// Rejects a caller whose stream ID does not parse.
+pub const BAD_REQUEST: SrtRejectReason = SrtRejectReason::Other(1400);
+/// For `RelayHooks::authorize` to turn a caller away with.
+pub const FORBIDDEN: SrtRejectReason = SrtRejectReason::Other(1403);
+/// Rejects `m=bidirectional`, which the relay does not serve.
+pub const BAD_MODE: SrtRejectReason = SrtRejectReason::Other(1405);
+/// Rejects a publisher to a resource that already has one.
+pub const CONFLICT: SrtRejectReason = SrtRejectReason::Other(1409);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamId {
+    pub resource: String,
+    pub mode: StreamMode,
+    pub user: Option<String>,
+    /// Keys other than `r`, `m` and `u`, in the order given.
+    pub other: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamMode {
+    Request,
+    Publish,
+    Bidirectional,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamIdError(String);
+
+impl Display for StreamIdError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid stream id {:?}", self.0)
+    }
+}
+
+impl Error for StreamIdError {}
+
+impl FromStr for StreamId {
+    type Err = StreamIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || StreamIdError(s.to_string());
+        let mut id = StreamId {
+            resource: String::new(),
+            mode: StreamMode::Request,
+            user: None,
+            other: Vec::new(),
+        };
+        let pairs = match s.strip_prefix("#!::") {
+            Some(pairs) => pairs,
+            None => {
+                id.resource = s.to_string();
+                return Ok(id);
+            }
+        };
+        for pair in pairs.split(',') {
+            let (key, value) = pair.split_once('=').ok_or_else(invalid)?;
+            match key {
+                "r" => id.resource = value.to_string(),
+                "u" => id.user = Some(value.to_string()),
+                "m" => {
+                    id.mode = match value {
+                        "request" => StreamMode::Request,
+                        "publish" => StreamMode::Publish,
+                        "bidirectional" => StreamMode::Bidirectional,
+                        _ => return Err(invalid()),
+                    }
+                }
+                _ => id.other.push((key.to_string(), value.to_string())),
+            }
+        }
+        Ok(id)
+    }
+}
+
+/// What to do when a subscriber's queue is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropPolicy {
+    DropNewest,
+    DropOldest,
+    Disconnect,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RelayConfig {
+    /// Payloads buffered per subscriber.
+    pub queue_len: usize,
+    pub drop_policy: DropPolicy,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            queue_len: 256,
+            drop_policy: DropPolicy::DropOldest,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionStats {
+    pub payloads: u64,
+    pub bytes: u64,
+    /// Payloads a subscriber missed because its queue was full.
+    pub dropped: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResourceStats {
+    pub resource: String,
+    pub publishing: bool,
+    pub subscribers: usize,
+    /// Counters of the current or last publisher.
+    pub published: ConnectionStats,
+}
+
+/// Extension points of a `Relay`. All methods have permissive defaults.
+pub trait RelayHooks: Send + Sync {
+    /// Called before a caller is attached. Under `serve` this happens before
+    /// the handshake is answered and an error rejects the caller with that
+    /// reason, which has to be `SrtRejectReason::Other` with a code of 1000
+    /// or up; a connection passed to `handle` is just closed. Callers that
+    /// pass are still rejected with `BAD_MODE` or `CONFLICT` when the relay
+    /// cannot serve them.
+    fn authorize(&self, _id: &StreamId, _peer: SocketAddr) -> Result<(), SrtRejectReason> {
+        Ok(())
+    }
+    fn on_connect(&self, _id: &StreamId, _peer: SocketAddr) {}
+    fn on_disconnect(&self, _id: &StreamId, _peer: SocketAddr, _stats: &ConnectionStats) {}
+}
+
+struct AllowAll;
+
+impl RelayHooks for AllowAll {}
+
+struct Queue {
+    payloads: VecDeque<Arc<[u8]>>,
+    waker: Option<Waker>,
+    closed: bool,
+    stats: ConnectionStats,
+}
+
+struct Subscriber {
+    queue: Mutex<Queue>,
+}
+
+impl Subscriber {
+    fn queue(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    fn push(&self, payload: &Arc<[u8]>, config: &RelayConfig) {
+        let mut queue = self.queue();
+        if queue.payloads.len() >= config.queue_len {
+            queue.stats.dropped += 1;
+            match config.drop_policy {
+                DropPolicy::DropNewest => return,
+                DropPolicy::DropOldest => {
+                    queue.payloads.pop_front();
+                }
+                DropPolicy::Disconnect => queue.closed = true,
+            }
+        }
+        if !queue.closed {
+            queue.payloads.push_back(payload.clone());
+        }
+        if let Some(waker) = queue.waker.take() {
+            waker.wake();
+        }
+    }
+    async fn next(&self) -> Option<Arc<[u8]>> {
+        future::poll_fn(|cx| {
+            let mut queue = self.queue();
+            if queue.closed {
+                return Poll::Ready(None);
+            }
+            match queue.payloads.pop_front() {
+                Some(payload) => Poll::Ready(Some(payload)),
+                None => {
+                    queue.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        })
+        .await
+    }
+}
+
+#[derive(Default)]
+struct Resource {
+    publishing: bool,
+    published: ConnectionStats,
+    subscribers: Vec<Arc<Subscriber>>,
+}
+
+struct Shared {
+    config: RelayConfig,
+    hooks: Box<dyn RelayHooks>,
+    resources: Mutex<HashMap<String, Resource>>,
+}
+
+impl Shared {
+    fn resources(&self) -> MutexGuard<'_, HashMap<String, Resource>> {
+        self.resources
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+    // Authorized callers the relay cannot serve are rejected too. Two
+    // publishers can still both get past this; the later one is closed.
+    fn admit(&self, id: &StreamId, peer: SocketAddr) -> Result<(), SrtRejectReason> {
+        self.hooks.authorize(id, peer)?;
+        match id.mode {
+            StreamMode::Request => Ok(()),
+            StreamMode::Publish => match self.resources().get(&id.resource) {
+                Some(resource) if resource.publishing => Err(CONFLICT),
+                _ => Ok(()),
+            },
+            StreamMode::Bidirectional => Err(BAD_MODE),
+        }
+    }
+    // Resources nobody publishes to or subscribes to are forgotten.
+    fn prune(resources: &mut HashMap<String, Resource>, name: &str) {
+        if let Some(resource) = resources.get(name) {
+            if !resource.publishing && resource.subscribers.is_empty() {
+                resources.remove(name);
+            }
+        }
+    }
+}
+
+/// Cloning gives another handle to the same relay.
+#[derive(Clone)]
+pub struct Relay {
+    shared: Arc<Shared>,
+}
+
+impl Relay {
+    pub fn new(config: RelayConfig) -> Self {
+        Self::with_hooks(config, AllowAll)
+    }
+    pub fn with_hooks(config: RelayConfig, hooks: impl RelayHooks + 'static) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                config,
+                hooks: Box::new(hooks),
+                resources: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+    pub fn stats(&self) -> Vec<ResourceStats> {
+        self.shared
+            .resources()
+            .iter()
+            .map(|(name, resource)| ResourceStats {
+                resource: name.clone(),
+                publishing: resource.publishing,
+                subscribers: resource.subscribers.len(),
+                published: resource.published,
+            })
+            .collect()
+    }
+    /// Accepts and serves callers until accepting fails. Every connection is
+    /// driven by the returned future, so it works with any executor. Callers
+    /// are authorized in `listener`'s listen callback, which this replaces
+    /// while serving.
+    pub async fn serve(&self, listener: &SrtAsyncListener) -> io::Result<()> {
+        let shared = self.shared.clone();
+        listen_callback::set(
+            &listener.socket,
+            Arc::new(move |stream_id: &str, peer: Option<SocketAddr>| {
+                let id = stream_id.parse().map_err(|_| BAD_REQUEST)?;
+                shared.admit(&id, peer.ok_or(BAD_REQUEST)?)
+            }),
+        )?;
+        let _hook = ListenHook(listener.socket);
+        let mut connections = FuturesUnordered::new();
+        let mut accept = listener.accept().fuse();
+        loop {
+            select! {
+                accepted = accept => {
+                    let (stream, peer) = accepted?;
+                    if let Some(id) = stream_id(&stream) {
+                        connections.push(self.attach(id, stream, peer));
+                    }
+                    accept = listener.accept().fuse();
+                }
+                _ = connections.select_next_some() => {}
+            }
+        }
+    }
+    /// Authorizes and serves one already accepted connection until it ends.
+    /// A connection the relay cannot serve is closed.
+    pub async fn handle(&self, stream: SrtAsyncStream, peer: SocketAddr) {
+        if let Some(id) = stream_id(&stream) {
+            if self.shared.admit(&id, peer).is_ok() {
+                self.attach(id, stream, peer).await;
+            }
+        }
+    }
+
+    async fn attach(&self, id: StreamId, stream: SrtAsyncStream, peer: SocketAddr) {
+        let hooks = &self.shared.hooks;
+        let stats = match id.mode {
+            StreamMode::Publish => self.publish(&id, stream, peer).await,
+            StreamMode::Request => self.subscribe(&id, stream, peer).await,
+            StreamMode::Bidirectional => None,
+        };
+        if let Some(stats) = stats {
+            hooks.on_disconnect(&id, peer, &stats);
+        }
+    }
+
+    async fn publish(
+        &self,
+        id: &StreamId,
+        mut stream: SrtAsyncStream,
+        peer: SocketAddr,
+    ) -> Option<ConnectionStats> {
+        {
+            let mut resources = self.shared.resources();
+            let resource = resources.entry(id.resource.clone()).or_default();
+            if resource.publishing {
+                return None;
+            }
+            resource.publishing = true;
+            resource.published = ConnectionStats::default();
+        }
+        self.shared.hooks.on_connect(id, peer);
+        let mut buf = vec![0; MAX_PAYLOAD_SIZE];
+        while let Ok(len @ 1..) = stream.read(&mut buf).await {
+            let payload: Arc<[u8]> = buf[..len].into();
+            let mut resources = self.shared.resources();
+            let resource = resources.get_mut(&id.resource)?;
+            resource.published.payloads += 1;
+            resource.published.bytes += len as u64;
+            for subscriber in &resource.subscribers {
+                subscriber.push(&payload, &self.shared.config);
+            }
+        }
+        let mut resources = self.shared.resources();
+        let resource = resources.get_mut(&id.resource)?;
+        resource.publishing = false;
+        let stats = resource.published;
+        Shared::prune(&mut resources, &id.resource);
+        Some(stats)
+    }
+
+    async fn subscribe(
+        &self,
+        id: &StreamId,
+        mut stream: SrtAsyncStream,
+        peer: SocketAddr,
+    ) -> Option<ConnectionStats> {
+        let subscriber = Arc::new(Subscriber {
+            queue: Mutex::new(Queue {
+                payloads: VecDeque::new(),
+                waker: None,
+                closed: false,
+                stats: ConnectionStats::default(),
+            }),
+        });
+        self.shared
+            .resources()
+            .entry(id.resource.clone())
+            .or_default()
+            .subscribers
+            .push(subscriber.clone());
+        self.shared.hooks.on_connect(id, peer);
+        while let Some(payload) = subscriber.next().await {
+            if stream.write_all(&payload).await.is_err() {
+                break;
+            }
+            let mut queue = subscriber.queue();
+            queue.stats.payloads += 1;
+            queue.stats.bytes += payload.len() as u64;
+        }
+        let mut resources = self.shared.resources();
+        if let Some(resource) = resources.get_mut(&id.resource) {
+            resource
+                .subscribers
+                .retain(|other| !Arc::ptr_eq(other, &subscriber));
+        }
+        Shared::prune(&mut resources, &id.resource);
+        let stats = subscriber.queue().stats;
+        Some(stats)
+    }
+}
+
+fn stream_id(stream: &SrtAsyncStream) -> Option<StreamId> {
+    stream.get_stream_id().ok()?.parse().ok()
+}
+
+// Takes the relay's listen callback off again, also when `serve` is dropped.
+struct ListenHook(SrtSocket);
+
+impl Drop for ListenHook {
+    fn drop(&mut self) {
+        listen_callback::remove(&self.0);
+    }
+}
@@ -0,0 +1,240 @@
+//! Rendezvous between peers that do not know which of each other's
+//! addresses can be reached, typically because of NAT.
+//!
+//! Both sides swap candidate addresses through a `Signaling` channel and
+//! start a rendezvous to every candidate of the other side at once, all from
+//! the same local port. The side whose candidate list sorts first picks the
+//! first pair that connects and nominates it with a one-byte message; the
+//! other side keeps the pair the nomination arrives on.
+
+use crate::{
+    error::{SrtError, SrtErrorKind},
+    socket::SrtSocket,
+    ConnectState, Epoll, SrtBuilder, SrtPreConnectOpt, SrtStream, SrtTransmissionType,
+};
+
+use libsrt_sys as srt;
+
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    io::{self, ErrorKind},
+    net::SocketAddr,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+const NOMINATION: &[u8] = b"N";
+
+/// Carries candidate addresses to the peer, e.g. over a web service.
+pub trait Signaling {
+    fn send_candidates(&mut self, candidates: &[SocketAddr]) -> io::Result<()>;
+    /// Blocks until the peer's candidates arrive, failing with
+    /// `ErrorKind::TimedOut` after `timeout`.
+    fn receive_candidates(&mut self, timeout: Duration) -> io::Result<Vec<SocketAddr>>;
+}
+
+/// Signaling between two ends in the same process.
+pub struct LocalSignaling {
+    tx: mpsc::Sender<Vec<SocketAddr>>,
+    rx: mpsc::Receiver<Vec<SocketAddr>>,
+}
+
+impl LocalSignaling {
+    pub fn pair() -> (Self, Self) {
+        let (a_tx, b_rx) = mpsc::channel();
+        let (b_tx, a_rx) = mpsc::channel();
+        (Self { tx: a_tx, rx: a_rx }, Self { tx: b_tx, rx: b_rx })
+    }
+}
+
+impl Signaling for LocalSignaling {
+    fn send_candidates(&mut self, candidates: &[SocketAddr]) -> io::Result<()> {
+        self.tx
+            .send(candidates.to_vec())
+            .map_err(|_| ErrorKind::BrokenPipe.into())
+    }
+    fn receive_candidates(&mut self, timeout: Duration) -> io::Result<Vec<SocketAddr>> {
+        self.rx.recv_timeout(timeout).map_err(|e| match e {
+            mpsc::RecvTimeoutError::Timeout => ErrorKind::TimedOut.into(),
+            mpsc::RecvTimeoutError::Disconnected => ErrorKind::BrokenPipe.into(),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum RendezvousError {
+    Signaling(io::Error),
+    /// The peer sent no candidates.
+    NoCandidates,
+    /// Every pair failed; the error of each candidate of the peer.
+    Failed(Vec<(SocketAddr, SrtError)>),
+    TimedOut,
+    Srt(SrtError),
+}
+
+impl Display for RendezvousError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RendezvousError::Signaling(e) => write!(f, "signaling failed: {}", e),
+            RendezvousError::NoCandidates => write!(f, "the peer has no candidates"),
+            RendezvousError::Failed(failures) => {
+                write!(f, "no candidate pair connected")?;
+                for (i, (addr, e)) in failures.iter().enumerate() {
+                    let separator = if i == 0 { ": " } else { ", " };
+                    write!(f, "{}{} ({})", separator, addr, e)?;
+                }
+                Ok(())
+            }
+            RendezvousError::TimedOut => write!(f, "rendezvous timed out"),
+            RendezvousError::Srt(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for RendezvousError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RendezvousError::Signaling(e) => Some(e),
+            RendezvousError::Srt(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<SrtError> for RendezvousError {
+    fn from(e: SrtError) -> Self {
+        RendezvousError::Srt(e)
+    }
+}
+
+type Result<T> = std::result::Result<T, RendezvousError>;
+
+pub struct Rendezvous {
+    opt_vec: Vec<SrtPreConnectOpt>,
+    local: SocketAddr,
+    candidates: Vec<SocketAddr>,
+    timeout: Duration,
+}
+
+impl Rendezvous {
+    /// Rendezvous from `local`, which needs a port. `local` is a candidate
+    /// itself unless its IP is unspecified.
+    ///
+    /// Fails with `SrtErrorKind::InvParam` unless `builder` uses the message
+    /// API, which keeps the nomination apart from the data behind it.
+    pub fn new(builder: SrtBuilder, local: SocketAddr) -> Result<Self> {
+        if !uses_message_api(&builder.opt_vec) {
+            return Err(SrtError::from(SrtErrorKind::InvParam).into());
+        }
+        let candidates = if local.ip().is_unspecified() {
+            Vec::new()
+        } else {
+            vec![local]
+        };
+        Ok(Self {
+            opt_vec: builder.opt_vec,
+            local,
+            candidates,
+            timeout: Duration::from_secs(10),
+        })
+    }
+    /// Also offers `addr`, e.g. the public address a NAT maps `local` to.
+    pub fn add_candidate(mut self, addr: SocketAddr) -> Self {
+        self.candidates.push(addr);
+        self
+    }
+    /// Limit for the whole exchange, 10 seconds by default.
+    pub fn set_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// Returns the connected stream and the peer candidate it went to.
+    pub fn connect(self, signaling: &mut dyn Signaling) -> Result<(SrtStream, SocketAddr)> {
+        let deadline = Instant::now() + self.timeout;
+        signaling
+            .send_candidates(&self.candidates)
+            .map_err(RendezvousError::Signaling)?;
+        let remote = signaling
+            .receive_candidates(deadline.saturating_duration_since(Instant::now()))
+            .map_err(RendezvousError::Signaling)?;
+        if remote.is_empty() {
+            return Err(RendezvousError::NoCandidates);
+        }
+        let nominating = self.candidates < remote;
+
+        let mut pairs = Vec::new();
+        let mut failures = Vec::new();
+        for &addr in &remote {
+            let builder = SrtBuilder {
+                opt_vec: self.opt_vec.clone(),
+            };
+            match builder.start_rendezvous(self.local, addr) {
+                Ok(connecting) => pairs.push((addr, connecting)),
+                Err(e) => failures.push((addr, e)),
+            }
+        }
+        let mut epoll = Epoll::new()?;
+        let connecting = srt::SRT_EPOLL_OPT::SRT_EPOLL_OUT | srt::SRT_EPOLL_OPT::SRT_EPOLL_ERR;
+        for (_, pair) in &pairs {
+            epoll.add(&pair.socket(), &connecting)?;
+        }
+        let nominated = srt::SRT_EPOLL_OPT::SRT_EPOLL_IN | srt::SRT_EPOLL_OPT::SRT_EPOLL_ERR;
+        while !pairs.is_empty() {
+            let mut i = 0;
+            while i < pairs.len() {
+                let (addr, pair) = &pairs[i];
+                match pair.poll_state() {
+                    ConnectState::Connecting => {}
+                    ConnectState::Connected if nominating => {
+                        pair.socket().send(NOMINATION)?;
+                        let (addr, pair) = pairs.swap_remove(i);
+                        return Ok((pair.into_stream()?, addr));
+                    }
+                    ConnectState::Connected => {
+                        if is_nominated(&pair.socket())? {
+                            let (addr, pair) = pairs.swap_remove(i);
+                            return Ok((pair.into_stream()?, addr));
+                        }
+                        epoll.update(&pair.socket(), &nominated)?;
+                    }
+                    ConnectState::Failed(e) => {
+                        failures.push((*addr, e));
+                        epoll.remove(&pair.socket())?;
+                        pairs.swap_remove(i);
+                        continue;
+                    }
+                }
+                i += 1;
+            }
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            if timeout == Duration::from_secs(0) {
+                return Err(RendezvousError::TimedOut);
+            }
+            if !pairs.is_empty() {
+                epoll.wait(timeout.as_millis() as i64)?;
+            }
+        }
+        Err(RendezvousError::Failed(failures))
+    }
+}
+
+// Live mode, the default, turns the message API on and file mode turns it
+// off; either can be overridden afterwards.
+fn uses_message_api(opt_vec: &[SrtPreConnectOpt]) -> bool {
+    opt_vec.iter().fold(true, |enabled, opt| match opt {
+        SrtPreConnectOpt::MessageApi(value) => *value,
+        SrtPreConnectOpt::TransType(SrtTransmissionType::Live) => true,
+        SrtPreConnectOpt::TransType(SrtTransmissionType::File) => false,
+        _ => enabled,
+    })
+}
+
+fn is_nominated(socket: &SrtSocket) -> std::result::Result<bool, SrtError> {
+    let mut buf = [0; 16];
+    match socket.recv(&mut buf) {
+        Ok(len) => Ok(&buf[..len] == NOMINATION),
+        Err(e) if e.kind() == SrtErrorKind::AsyncRcv => Ok(false),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{
+    error::{SrtError, SrtErrorKind},
+    socket::{SrtSocket, SrtSocketStatus, DRAIN_POLL_INTERVAL},
+};
+
+use std::{
+    sync::{Mutex, MutexGuard, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+struct Tracked {
+    socket: SrtSocket,
+    listener: Option<SrtSocket>,
+}
+
+static OPEN_SOCKETS: Mutex<Vec<Tracked>> = Mutex::new(Vec::new());
+
+fn open_sockets_lock() -> MutexGuard<'static, Vec<Tracked>> {
+    OPEN_SOCKETS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(crate) fn track(socket: SrtSocket, listener: Option<SrtSocket>) {
+    open_sockets_lock().push(Tracked { socket, listener });
+}
+
+/// Returns whether `socket` was still being tracked.
+pub(crate) fn untrack(socket: SrtSocket) -> bool {
+    let mut open_sockets = open_sockets_lock();
+    let before = open_sockets.len();
+    open_sockets.retain(|tracked| tracked.socket.id != socket.id);
+    open_sockets.len() != before
+}
+
+pub fn open_sockets() -> Vec<SrtSocket> {
+    open_sockets_lock()
+        .iter()
+        .map(|tracked| tracked.socket)
+        .collect()
+}
+
+fn is_finished(socket: &SrtSocket) -> bool {
+    match socket.get_socket_state() {
+        Ok(SrtSocketStatus::Broken)
+        | Ok(SrtSocketStatus::Closing)
+        | Ok(SrtSocketStatus::Closed)
+        | Ok(SrtSocketStatus::NonExist)
+        | Err(_) => true,
+        Ok(_) => false,
+    }
+}
+
+/// Connections accepted from `listener` that are still open and not yet
+/// broken by the peer.
+pub(crate) fn accepted_from(listener: SrtSocket) -> Vec<SrtSocket> {
+    let accepted: Vec<_> = open_sockets_lock()
+        .iter()
+        .filter(|tracked| tracked.listener.map(|l| l.id) == Some(listener.id))
+        .map(|tracked| tracked.socket)
+        .collect();
+    accepted
+        .into_iter()
+        .filter(|socket| !is_finished(socket))
+        .collect()
+}
+
+pub(crate) fn is_expired(deadline: Option<Instant>) -> bool {
+    matches!(deadline, Some(deadline) if Instant::now() >= deadline)
+}
+
+/// Closes whatever `listener` accepted and is still running once the
+/// deadline has passed.
+pub(crate) fn close_accepted(listener: SrtSocket) -> Result<()> {
+    let remaining = accepted_from(listener);
+    if remaining.is_empty() {
+        return Ok(());
+    }
+    for socket in remaining {
+        let _ = socket.close();
+    }
+    Err(SrtErrorKind::Timeout.into())
+}
+
+pub(crate) fn wait_accepted(listener: SrtSocket, deadline: Option<Instant>) -> Result<()> {
+    while !accepted_from(listener).is_empty() {
+        if is_expired(deadline) {
+            return close_accepted(listener);
+        }
+        thread::sleep(DRAIN_POLL_INTERVAL);
+    }
+    Ok(())
+}
+
+/// Stops every socket opened through this crate: listeners are closed first
+/// so no new connections arrive, then each connection is given until
+/// `timeout` to have its queued data acknowledged before being closed.
+///
+/// All sockets are closed even on error; the first error met is returned.
+pub fn graceful_stop(timeout: Option<Duration>) -> Result<()> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let (listeners, connections): (Vec<_>, Vec<_>) = open_sockets()
+        .into_iter()
+        .partition(|socket| matches!(socket.get_socket_state(), Ok(SrtSocketStatus::Listening)));
+    let mut result = Ok(());
+    for listener in listeners {
+        result = result.and(listener.close());
+    }
+    for socket in &connections {
+        result = result.and(socket.drain(deadline));
+    }
+    for socket in connections {
+        result = result.and(socket.close());
+    }
+    result
+}
@@ -1,6 +1,7 @@
 use crate::error::{self, handle_result};
+use crate::{connect_callback, context, listen_callback, shutdown};
 
-use error::SrtError;
+use error::{SrtError, SrtErrorKind, SrtRejectReason};
 use libsrt_sys as srt;
 use os_socketaddr::{self, OsSocketAddr};
 use srt::sockaddr;
@@ -8,21 +9,28 @@ use srt::sockaddr;
 use std::{
     convert::TryInto,
     ffi::c_void,
-    iter::FromIterator,
     mem,
-    net::{SocketAddr, ToSocketAddrs},
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
     os::raw::{c_char, c_int},
+    thread,
+    time::{Duration, Instant},
 };
 use std::num::NonZeroI64;
 
 #[cfg(target_family = "unix")]
 use libc::linger;
+#[cfg(target_family = "unix")]
+use std::os::unix::io::AsRawFd;
 
+#[cfg(target_os = "windows")]
+use std::os::windows::io::AsRawSocket;
 #[cfg(target_os = "windows")]
 use winapi::um::winsock2::linger;
 
 type Result<T> = std::result::Result<T, SrtError>;
 
+pub(crate) const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
 pub enum SrtSocketStatus {
     Init,
     Opened,
@@ -43,11 +51,17 @@ pub struct SrtSocket {
 //General methods
 impl SrtSocket {
     pub fn new() -> Result<Self> {
+        context::retain()?;
         let result = unsafe { srt::srt_create_socket() };
         if result == -1 {
-            error::handle_result(Self { id: 0 }, result)
+            let created = error::handle_result(Self { id: 0 }, result);
+            // The creation error is the one worth reporting.
+            let _ = context::release();
+            created
         } else {
-            Ok(Self { id: result })
+            let socket = Self { id: result };
+            shutdown::track(socket, None);
+            Ok(socket)
         }
     }
     pub fn bind<A: ToSocketAddrs>(self, addrs: A) -> Result<Self> {
@@ -64,20 +78,34 @@ impl SrtSocket {
                 return error::handle_result(self, result);
             }
         }
-        Err(SrtError::SockFail)
+        Err(SrtErrorKind::SockFail.into())
+    }
+    /// Binds to an existing UDP socket. libsrt takes ownership of it and
+    /// closes it along with the last SRT socket using it.
+    pub fn bind_acquire(self, udp: UdpSocket) -> Result<Self> {
+        #[cfg(target_family = "unix")]
+        let raw = udp.as_raw_fd();
+        #[cfg(target_os = "windows")]
+        let raw = udp.as_raw_socket() as srt::UDPSOCKET;
+        let result = unsafe { srt::srt_bind_acquire(self.id, raw) };
+        // Ownership only passes on success; otherwise dropping `udp` closes it.
+        if result != -1 {
+            mem::forget(udp);
+        }
+        error::handle_result(self, result)
     }
     pub fn rendezvous<A: ToSocketAddrs>(&self, local: A, remote: A) -> Result<()> {
         let local_addr;
         if let Ok(mut addr) = local.to_socket_addrs() {
             local_addr = addr.next()
         } else {
-            return Err(SrtError::SockFail);
+            return Err(SrtErrorKind::SockFail.into());
         };
         let remote_addr;
         if let Ok(mut addr) = remote.to_socket_addrs() {
             remote_addr = addr.next()
         } else {
-            return Err(SrtError::SockFail);
+            return Err(SrtErrorKind::SockFail.into());
         };
 
         if let (Some(local), Some(remote)) = (local_addr, remote_addr) {
@@ -94,7 +122,7 @@ impl SrtSocket {
             };
             error::handle_result((), result)
         } else {
-            Err(SrtError::SockFail)
+            Err(SrtErrorKind::SockFail.into())
         }
     }
     pub fn connect<A: ToSocketAddrs>(&self, addrs: A) -> Result<()> {
@@ -103,10 +131,10 @@ impl SrtSocket {
             if let Some(addr) = target.next() {
                 target_addr = addr;
             } else {
-                return Err(SrtError::SockFail);
+                return Err(SrtErrorKind::SockFail.into());
             }
         } else {
-            return Err(SrtError::SockFail);
+            return Err(SrtErrorKind::SockFail.into());
         };
         let os_target: OsSocketAddr = target_addr.into();
         let result = unsafe {
@@ -116,13 +144,26 @@ impl SrtSocket {
                 os_target.len() as i32,
             )
         };
-        return error::handle_result((), result);
+        // libsrt's error code does not say why the peer rejected.
+        error::handle_result((), result).map_err(|e| match e.kind() {
+            SrtErrorKind::ConnRej(SrtRejectReason::Unknown) => {
+                e.with_kind(SrtErrorKind::ConnRej(self.get_reject_reason()))
+            }
+            _ => e,
+        })
     }
     pub fn listen(&self, backlog: i32) -> Result<()> {
         let result = unsafe { srt::srt_listen(self.id, backlog) };
         error::handle_result((), result)
     }
     pub fn bistats(&self) -> Result<srt::SRT_TRACEBSTATS> {
+        self.bstats(true)
+    }
+    /// Like `bistats`, but leaves the interval counters running.
+    pub(crate) fn bistats_total(&self) -> Result<srt::SRT_TRACEBSTATS> {
+        self.bstats(false)
+    }
+    fn bstats(&self, clear: bool) -> Result<srt::SRT_TRACEBSTATS> {
         let mut stats = srt::SRT_TRACEBSTATS {
             msTimeStamp: 0,
             pktSentTotal: 0,
@@ -211,7 +252,7 @@ impl SrtSocket {
             srt::srt_bstats(
                 self.id,
                 &mut stats,
-                1
+                clear as c_int
             )
         };
         handle_result(stats, result)
@@ -265,12 +306,47 @@ impl SrtSocket {
         if result == -1 {
             error::handle_result((Self { id: 0 }, "0.0.0.0:0".parse().unwrap()), result)
         } else {
-            Ok((Self { id: result }, addr.into_addr().unwrap()))
+            let socket = Self { id: result };
+            if let Err(e) = context::retain() {
+                unsafe { srt::srt_close(socket.id) };
+                return Err(e);
+            }
+            shutdown::track(socket, Some(*self));
+            Ok((socket, addr.into_addr().unwrap()))
         }
     }
     pub fn close(self) -> Result<()> {
+        let tracked = shutdown::untrack(self);
         let result = unsafe { srt::srt_close(self.id) };
-        error::handle_result((), result)
+        connect_callback::remove(&self);
+        listen_callback::remove(&self);
+        let closed = error::handle_result((), result);
+        if tracked {
+            context::release()?;
+        }
+        closed
+    }
+    /// Whether everything handed to `send` has been acknowledged by the peer.
+    /// Unacknowledged data on a broken connection can never drain and is
+    /// reported as `SrtErrorKind::ConnLost`.
+    pub fn is_drained(&self) -> Result<bool> {
+        match self.get_socket_state()? {
+            SrtSocketStatus::Connected => Ok(self.get_sender_buffer()?.1 == 0),
+            SrtSocketStatus::Broken => match self.get_sender_buffer() {
+                Ok((_, 0)) => Ok(true),
+                _ => Err(SrtErrorKind::ConnLost.into()),
+            },
+            _ => Ok(true),
+        }
+    }
+    pub fn drain(&self, deadline: Option<Instant>) -> Result<()> {
+        while !self.is_drained()? {
+            if shutdown::is_expired(deadline) {
+                return Err(SrtErrorKind::Timeout.into());
+            }
+            thread::sleep(DRAIN_POLL_INTERVAL);
+        }
+        Ok(())
     }
     pub fn send(&self, buf: &[u8]) -> Result<usize> {
         let result = unsafe {
@@ -323,6 +399,44 @@ impl SrtSocket {
             ))
         }
     }
+    /// Sends one message stamped with `src_time`, a `time_now()` value, as
+    /// the time it entered the stream; `None` stamps it with the current
+    /// time.
+    pub fn sendmsg2(&self, buf: &[u8], src_time: Option<NonZeroI64>) -> Result<usize> {
+        let mut msg_ctl = libsrt_sys::SRT_MSGCTRL {
+            flags: 0,
+            msgttl: -1,
+            inorder: 0,
+            boundary: 0,
+            srctime: src_time.map_or(0, NonZeroI64::get),
+            pktseq: -1,
+            msgno: -1,
+            grpdata: std::ptr::null_mut() as *mut libsrt_sys::SRT_SOCKGROUPDATA,
+            grpdata_size: 0,
+        };
+        let result = unsafe {
+            srt::srt_sendmsg2(
+                self.id,
+                buf as *const [u8] as *const c_char,
+                buf.len() as i32,
+                &mut msg_ctl as *mut _,
+            )
+        };
+        if result == -1 {
+            Err(error::get_last_error())
+        } else {
+            Ok(result as usize)
+        }
+    }
+    /// When the connection was made, on the `time_now()` clock.
+    pub fn connection_time(&self) -> Result<i64> {
+        let time = unsafe { srt::srt_connection_time(self.id) };
+        if time < 0 {
+            Err(error::get_last_error())
+        } else {
+            Ok(time)
+        }
+    }
     pub fn get_sender_buffer(&self) -> Result<(usize, usize)> {
         let mut blocks = 0;
         let mut bytes = 0;
@@ -346,10 +460,7 @@ impl SrtSocket {
                 &mut _optlen as *mut c_int,
             )
         };
-        error::handle_result(
-            srt::SRT_EPOLL_OPT(events.try_into().expect("invalid events")),
-            result,
-        )
+        error::handle_result(srt::SRT_EPOLL_OPT(events as u32), result)
     }
 }
 
@@ -534,6 +645,41 @@ impl SrtSocket {
         };
         error::handle_result(enabled, result)
     }
+    pub fn get_packet_filter(&self) -> Result<String> {
+        let mut filter = vec![0u8; 512];
+        let mut filter_len = filter.len() as i32;
+        let result = unsafe {
+            srt::srt_getsockflag(
+                self.id,
+                srt::SRT_SOCKOPT::SRTO_PACKETFILTER,
+                filter.as_mut_ptr() as *mut c_void,
+                &mut filter_len as *mut c_int,
+            )
+        };
+        error::handle_result((), result)?;
+        filter.truncate(filter_len as usize);
+        String::from_utf8(filter).map_err(|_| SrtErrorKind::InvParam.into())
+    }
+    pub fn get_crypto_mode(&self) -> Result<SrtCryptoMode> {
+        check_aead_api()?;
+        let mut mode = 0;
+        let mut _optlen = mem::size_of::<i32>() as i32;
+        let result = unsafe {
+            srt::srt_getsockflag(
+                self.id,
+                srt::SRT_SOCKOPT::SRTO_CRYPTOMODE,
+                &mut mode as *mut c_int as *mut c_void,
+                &mut _optlen as *mut c_int,
+            )
+        };
+        let mode = match mode {
+            0 => SrtCryptoMode::Auto,
+            1 => SrtCryptoMode::AesCtr,
+            2 => SrtCryptoMode::AesGcm,
+            _ => return Err(SrtErrorKind::InvParam.into()),
+        };
+        error::handle_result(mode, result)
+    }
     pub fn get_encryption_key_length(&self) -> Result<i32> {
         let mut len = 0;
         let mut _optlen = mem::size_of::<i32>() as i32;
@@ -653,7 +799,10 @@ impl SrtSocket {
     }
     pub fn get_reject_reason(&self) -> error::SrtRejectReason {
         let result = unsafe { srt::srt_getrejectreason(self.id) };
-        srt::SRT_REJECT_REASON(result.try_into().expect("invalid reject code")).into()
+        match result.try_into() {
+            Ok(code) => srt::SRT_REJECT_REASON(code).into(),
+            Err(_) => error::SrtRejectReason::Unknown,
+        }
     }
     pub fn get_rendezvous(&self) -> Result<bool> {
         let mut rendezvous = false;
@@ -764,18 +913,19 @@ impl SrtSocket {
         error::handle_result(state, 0)
     }
     pub fn get_stream_id(&self) -> Result<String> {
-        let mut id = String::from_iter([' '; 512].iter());
-        let mut id_len = mem::size_of_val(&id) as i32;
+        let mut id = vec![0u8; 512];
+        let mut id_len = id.len() as i32;
         let result = unsafe {
             srt::srt_getsockflag(
                 self.id,
-                srt::SRT_SOCKOPT::SRTO_STATE,
+                srt::SRT_SOCKOPT::SRTO_STREAMID,
                 id.as_mut_ptr() as *mut c_void,
                 &mut id_len as *mut c_int,
             )
         };
+        error::handle_result((), result)?;
         id.truncate(id_len as usize);
-        error::handle_result(id, result)
+        String::from_utf8(id).map_err(|_| SrtErrorKind::InvParam.into())
     }
     pub fn get_too_late_packet_drop(&self) -> Result<bool> {
         let mut enable = true;
@@ -1106,8 +1256,8 @@ impl SrtSocket {
             srt::srt_setsockflag(
                 self.id,
                 srt::SRT_SOCKOPT::SRTO_PACKETFILTER,
-                filter[..512].as_ptr() as *const c_void,
-                filter[..512].len() as i32,
+                filter.as_ptr() as *const c_void,
+                filter.len() as i32,
             )
         };
         error::handle_result((), result)
@@ -1134,6 +1284,23 @@ impl SrtSocket {
         };
         error::handle_result((), result)
     }
+    pub fn set_crypto_mode(&self, mode: SrtCryptoMode) -> Result<()> {
+        check_aead_api()?;
+        let mode: c_int = match mode {
+            SrtCryptoMode::Auto => 0,
+            SrtCryptoMode::AesCtr => 1,
+            SrtCryptoMode::AesGcm => 2,
+        };
+        let result = unsafe {
+            srt::srt_setsockflag(
+                self.id,
+                srt::SRT_SOCKOPT::SRTO_CRYPTOMODE,
+                &mode as *const c_int as *const c_void,
+                mem::size_of::<i32>() as c_int,
+            )
+        };
+        error::handle_result((), result)
+    }
     pub fn set_encryption_key_length(&self, bytes: i32) -> Result<()> {
         let result = unsafe {
             srt::srt_setsockflag(
@@ -1381,7 +1548,7 @@ impl SrtSocket {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum SrtKmState {
     Unsecured,
     Securing,
@@ -1398,8 +1565,30 @@ pub enum SrtTransmissionType {
     Invalid,
 }
 
+// AES-GCM needs libsrt built against OpenSSL EVP (the `crypto-openssl-evp`
+// feature); `Auto` lets a listener follow whatever the caller asked for.
+// Any mode needs libsrt's AEAD API, see `libsrt_sys::AEAD_API`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SrtCryptoMode {
+    Auto,
+    AesCtr,
+    AesGcm,
+}
+
 #[derive(Copy, Clone)]
 pub enum SrtCongestionController {
     Live,
     File,
 }
+
+// libsrt without the AEAD API would reject SRTO_CRYPTOMODE as an unknown
+// option, with nothing to say why.
+fn check_aead_api() -> Result<()> {
+    if srt::AEAD_API {
+        Ok(())
+    } else {
+        Err(SrtError::from(SrtErrorKind::InvOp).with_message(
+            "libsrt was built without ENABLE_AEAD_API_PREVIEW, so it has no crypto mode",
+        ))
+    }
+}
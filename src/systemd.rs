@@ -0,0 +1,187 @@
+//! Listeners on UDP sockets passed in by systemd socket activation, see
+//! sd_listen_fds(3).
+
+use crate::{SrtAsyncBuilder, SrtAsyncListener, SrtBuilder, SrtListener};
+
+use std::{
+    env,
+    io::{self, ErrorKind},
+    mem,
+    net::{SocketAddr, UdpSocket},
+    os::unix::io::{FromRawFd, RawFd},
+    process,
+};
+
+const LISTEN_FDS_START: RawFd = 3;
+
+/// Selects one of the inherited sockets.
+#[derive(Clone, Copy, Debug)]
+pub enum Match<'a> {
+    /// The `FileDescriptorName=` of the socket unit.
+    Name(&'a str),
+    /// The address the socket is bound to.
+    Addr(SocketAddr),
+}
+
+/// The inherited UDP sockets not taken yet. Dropping it closes them.
+pub struct ListenFds {
+    sockets: Vec<(Option<String>, UdpSocket)>,
+}
+
+impl ListenFds {
+    /// Takes the sockets systemd passed to this process and clears the
+    /// `LISTEN_*` variables so children do not see them. Inherited fds that
+    /// are not UDP sockets are left alone. Returns nothing if the process
+    /// was not socket activated.
+    ///
+    /// Changing the environment races with other threads reading it, so
+    /// call this before spawning any, or use `from_env_keep_vars`.
+    pub fn from_env() -> io::Result<Self> {
+        let listen_fds = Self::from_env_keep_vars()?;
+        for key in &["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+            env::remove_var(key);
+        }
+        Ok(listen_fds)
+    }
+
+    /// Like `from_env`, but leaves the `LISTEN_*` variables set. They name
+    /// this process, so children still ignore them.
+    pub fn from_env_keep_vars() -> io::Result<Self> {
+        let vars = |key: &str| env::var(key).ok();
+        let names = parse_env(&vars, process::id())?;
+        let fds = (LISTEN_FDS_START..).zip(names);
+        Ok(unsafe { Self::from_fds(fds) })
+    }
+
+    /// Wraps already inherited fds.
+    ///
+    /// # Safety
+    ///
+    /// Every fd must be open and owned by nobody else; the UDP ones are
+    /// closed when no longer used.
+    pub unsafe fn from_fds(fds: impl IntoIterator<Item = (RawFd, Option<String>)>) -> Self {
+        let sockets = fds
+            .into_iter()
+            .filter(|(fd, _)| is_udp_socket(*fd))
+            .map(|(fd, name)| {
+                libc::fcntl(fd, libc::F_SETFD, libc::FD_CLOEXEC);
+                (name, UdpSocket::from_raw_fd(fd))
+            })
+            .collect();
+        Self { sockets }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sockets.is_empty()
+    }
+
+    /// Removes and returns the first socket matching `m`.
+    pub fn take(&mut self, m: Match<'_>) -> Option<UdpSocket> {
+        let index = self.sockets.iter().position(|(name, socket)| match m {
+            Match::Name(wanted) => name.as_deref() == Some(wanted),
+            Match::Addr(addr) => socket.local_addr().ok() == Some(addr),
+        })?;
+        Some(self.sockets.remove(index).1)
+    }
+
+    pub fn take_all(&mut self) -> Vec<UdpSocket> {
+        mem::take(&mut self.sockets)
+            .into_iter()
+            .map(|(_, socket)| socket)
+            .collect()
+    }
+
+    pub fn listen(
+        &mut self,
+        m: Match<'_>,
+        builder: SrtBuilder,
+        backlog: i32,
+    ) -> io::Result<SrtListener> {
+        let socket = self.take(m).ok_or_else(|| not_found(m))?;
+        Ok(builder.bind_acquire(socket)?.listen(backlog)?)
+    }
+
+    pub fn listen_async(
+        &mut self,
+        m: Match<'_>,
+        builder: SrtAsyncBuilder,
+        backlog: i32,
+    ) -> io::Result<SrtAsyncListener> {
+        let socket = self.take(m).ok_or_else(|| not_found(m))?;
+        Ok(builder.bind_acquire(socket)?.listen(backlog)?)
+    }
+}
+
+fn not_found(m: Match<'_>) -> io::Error {
+    io::Error::new(
+        ErrorKind::NotFound,
+        format!("no inherited UDP socket matches {:?}", m),
+    )
+}
+
+/// The names of the fds passed to process `pid`, one entry per fd.
+pub(crate) fn parse_env(
+    vars: &dyn Fn(&str) -> Option<String>,
+    pid: u32,
+) -> io::Result<Vec<Option<String>>> {
+    let invalid = |key: &str| io::Error::new(ErrorKind::InvalidData, format!("invalid {}", key));
+    let listen_pid = match vars("LISTEN_PID") {
+        Some(listen_pid) => listen_pid
+            .parse::<u32>()
+            .map_err(|_| invalid("LISTEN_PID"))?,
+        None => return Ok(Vec::new()),
+    };
+    // Meant for another process, e.g. our parent.
+    if listen_pid != pid {
+        return Ok(Vec::new());
+    }
+    let count = match vars("LISTEN_FDS") {
+        Some(count) => count.parse::<usize>().map_err(|_| invalid("LISTEN_FDS"))?,
+        None => return Ok(Vec::new()),
+    };
+    match vars("LISTEN_FDNAMES") {
+        Some(names) => {
+            let names: Vec<_> = names
+                .split(':')
+                .map(|name| Some(name.to_string()).filter(|name| !name.is_empty()))
+                .collect();
+            if names.len() != count {
+                return Err(invalid("LISTEN_FDNAMES"));
+            }
+            Ok(names)
+        }
+        None => Ok(vec![None; count]),
+    }
+}
+
+// A datagram socket of an IP family; AF_UNIX ones are datagram sockets too.
+fn is_udp_socket(fd: RawFd) -> bool {
+    let mut kind: libc::c_int = 0;
+    let mut len = mem::size_of::<libc::c_int>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_TYPE,
+            &mut kind as *mut libc::c_int as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    if result != 0 || kind != libc::SOCK_DGRAM {
+        return false;
+    }
+    let mut addr: libc::sockaddr_storage = unsafe { mem::zeroed() };
+    let mut len = mem::size_of::<libc::sockaddr_storage>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockname(
+            fd,
+            &mut addr as *mut libc::sockaddr_storage as *mut libc::sockaddr,
+            &mut len,
+        )
+    };
+    result == 0
+        && matches!(
+            addr.ss_family as libc::c_int,
+            libc::AF_INET | libc::AF_INET6
+        )
+}
@@ -0,0 +1,318 @@
+//! A lossy UDP proxy to put between two SRT endpoints in tests.
+//!
+//! The caller connects to `ImpairmentProxy::addr` instead of the listener;
+//! every datagram then goes through the `Impairment` of its direction.
+
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    io::{self, ErrorKind},
+    net::{SocketAddr, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+const MAX_DATAGRAM_SIZE: usize = 65536;
+const TICK: Duration = Duration::from_millis(1);
+
+/// Gilbert-Elliott loss: every datagram is lost while in the bad state.
+#[derive(Clone, Copy, Debug)]
+pub struct BurstLoss {
+    /// Chance per datagram of going from the good to the bad state.
+    pub enter: f64,
+    /// Chance per datagram of going back.
+    pub exit: f64,
+}
+
+/// What happens to datagrams in one direction. The default is a perfect
+/// link.
+#[derive(Clone, Copy, Debug)]
+pub struct Impairment {
+    /// Chance of losing a datagram.
+    pub loss: f64,
+    pub burst_loss: Option<BurstLoss>,
+    pub delay: Duration,
+    /// The delay varies by up to this much either way, which reorders
+    /// datagrams too.
+    pub jitter: Duration,
+    /// Chance of holding a datagram back by `reorder_delay`.
+    pub reorder: f64,
+    pub reorder_delay: Duration,
+    /// Chance of sending a datagram twice.
+    pub duplicate: f64,
+    /// Link rate in bits per second; datagrams queue up behind it.
+    pub bandwidth: Option<u64>,
+}
+
+impl Default for Impairment {
+    fn default() -> Self {
+        Self {
+            loss: 0.0,
+            burst_loss: None,
+            delay: Duration::from_secs(0),
+            jitter: Duration::from_secs(0),
+            reorder: 0.0,
+            reorder_delay: Duration::from_millis(20),
+            duplicate: 0.0,
+            bandwidth: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// From the caller to the listener.
+    Upstream,
+    /// From the listener to the caller.
+    Downstream,
+}
+
+#[derive(Debug, Default)]
+pub struct ProxyStats {
+    received: AtomicU64,
+    sent: AtomicU64,
+    lost: AtomicU64,
+    duplicated: AtomicU64,
+    reordered: AtomicU64,
+}
+
+impl ProxyStats {
+    pub fn received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+    /// Datagrams sent on, duplicates included.
+    pub fn sent(&self) -> u64 {
+        self.sent.load(Ordering::Relaxed)
+    }
+    pub fn lost(&self) -> u64 {
+        self.lost.load(Ordering::Relaxed)
+    }
+    pub fn duplicated(&self) -> u64 {
+        self.duplicated.load(Ordering::Relaxed)
+    }
+    pub fn reordered(&self) -> u64 {
+        self.reordered.load(Ordering::Relaxed)
+    }
+    fn add(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+struct Link {
+    impairment: Mutex<Impairment>,
+    stats: ProxyStats,
+}
+
+pub struct ImpairmentProxy {
+    addr: SocketAddr,
+    upstream: Arc<Link>,
+    downstream: Arc<Link>,
+    stop: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl ImpairmentProxy {
+    /// Proxies to the listener at `listener`, with the same impairment both
+    /// ways. `seed` makes the random choices repeatable.
+    pub fn start(listener: SocketAddr, impairment: Impairment, seed: u64) -> io::Result<Self> {
+        let front = UdpSocket::bind(("127.0.0.1", 0))?;
+        let back = UdpSocket::bind(("127.0.0.1", 0))?;
+        back.connect(listener)?;
+        front.set_read_timeout(Some(TICK))?;
+        back.set_read_timeout(Some(TICK))?;
+        let link = || {
+            Arc::new(Link {
+                impairment: Mutex::new(impairment),
+                stats: ProxyStats::default(),
+            })
+        };
+        let (upstream, downstream) = (link(), link());
+        let stop = Arc::new(AtomicBool::new(false));
+        let caller = Arc::new(Mutex::new(None));
+        let addr = front.local_addr()?;
+
+        let threads = vec![
+            spawn_forwarder(
+                Endpoint::Front(front.try_clone()?, caller.clone()),
+                Endpoint::Back(back.try_clone()?),
+                upstream.clone(),
+                stop.clone(),
+                seed,
+            ),
+            spawn_forwarder(
+                Endpoint::Back(back),
+                Endpoint::Front(front, caller),
+                downstream.clone(),
+                stop.clone(),
+                seed.wrapping_add(1),
+            ),
+        ];
+        Ok(Self {
+            addr,
+            upstream,
+            downstream,
+            stop,
+            threads,
+        })
+    }
+    /// Where the caller connects to.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+    /// Takes effect for datagrams received from now on.
+    pub fn set_impairment(&self, direction: Direction, impairment: Impairment) {
+        *self
+            .link(direction)
+            .impairment
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = impairment;
+    }
+    pub fn stats(&self, direction: Direction) -> &ProxyStats {
+        &self.link(direction).stats
+    }
+    fn link(&self, direction: Direction) -> &Link {
+        match direction {
+            Direction::Upstream => &self.upstream,
+            Direction::Downstream => &self.downstream,
+        }
+    }
+}
+
+impl Drop for ImpairmentProxy {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+// The front socket answers whoever sent to it last, the caller.
+enum Endpoint {
+    Front(UdpSocket, Arc<Mutex<Option<SocketAddr>>>),
+    Back(UdpSocket),
+}
+
+impl Endpoint {
+    fn recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Endpoint::Front(socket, caller) => {
+                let (len, from) = socket.recv_from(buf)?;
+                *caller.lock().unwrap_or_else(PoisonError::into_inner) = Some(from);
+                Ok(len)
+            }
+            Endpoint::Back(socket) => socket.recv(buf),
+        }
+    }
+    fn send(&self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Endpoint::Front(socket, caller) => {
+                match *caller.lock().unwrap_or_else(PoisonError::into_inner) {
+                    Some(caller) => socket.send_to(buf, caller),
+                    None => Err(ErrorKind::NotConnected.into()),
+                }
+            }
+            Endpoint::Back(socket) => socket.send(buf),
+        }
+    }
+}
+
+fn spawn_forwarder(
+    from: Endpoint,
+    to: Endpoint,
+    link: Arc<Link>,
+    stop: Arc<AtomicBool>,
+    seed: u64,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut rng = Rng(seed | 1);
+        let mut bad_state = false;
+        let mut link_free_at = Instant::now();
+        let mut sequence = 0u64;
+        // Datagrams by departure time; the sequence keeps equal times in
+        // arrival order.
+        let mut queue: BinaryHeap<Reverse<(Instant, u64, Vec<u8>)>> = BinaryHeap::new();
+        let mut buf = vec![0; MAX_DATAGRAM_SIZE];
+        while !stop.load(Ordering::Relaxed) {
+            let now = Instant::now();
+            while matches!(queue.peek(), Some(Reverse((departure, _, _))) if *departure <= now) {
+                if let Some(Reverse((_, _, datagram))) = queue.pop() {
+                    if to.send(&datagram).is_ok() {
+                        ProxyStats::add(&link.stats.sent);
+                    }
+                }
+            }
+            // Times out every tick so due datagrams go out.
+            let len = match from.recv(&mut buf) {
+                Ok(len) => len,
+                Err(_) => continue,
+            };
+            ProxyStats::add(&link.stats.received);
+            let impairment = *link
+                .impairment
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if let Some(burst) = impairment.burst_loss {
+                let change = if bad_state { burst.exit } else { burst.enter };
+                if rng.chance(change) {
+                    bad_state = !bad_state;
+                }
+            } else {
+                bad_state = false;
+            }
+            if bad_state || rng.chance(impairment.loss) {
+                ProxyStats::add(&link.stats.lost);
+                continue;
+            }
+            let mut departure = Instant::now() + impairment.delay;
+            let jitter = impairment.jitter.as_secs_f64() * (2.0 * rng.next() - 1.0);
+            departure = if jitter < 0.0 {
+                departure
+                    .checked_sub(Duration::from_secs_f64(-jitter))
+                    .unwrap_or(departure)
+            } else {
+                departure + Duration::from_secs_f64(jitter)
+            };
+            if rng.chance(impairment.reorder) {
+                ProxyStats::add(&link.stats.reordered);
+                departure += impairment.reorder_delay;
+            }
+            if let Some(bandwidth) = impairment.bandwidth {
+                let start = departure.max(link_free_at);
+                link_free_at =
+                    start + Duration::from_secs_f64(len as f64 * 8.0 / bandwidth.max(1) as f64);
+                departure = link_free_at;
+            }
+            let copies = if rng.chance(impairment.duplicate) {
+                ProxyStats::add(&link.stats.duplicated);
+                2
+            } else {
+                1
+            };
+            for _ in 0..copies {
+                sequence += 1;
+                queue.push(Reverse((departure, sequence, buf[..len].to_vec())));
+            }
+        }
+    })
+}
+
+// xorshift64; tests want repeatable, not strong, randomness.
+struct Rng(u64);
+
+impl Rng {
+    /// Uniform in [0, 1).
+    fn next(&mut self) -> f64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+    fn chance(&mut self, probability: f64) -> bool {
+        probability > 0.0 && self.next() < probability
+    }
+}
@@ -0,0 +1,69 @@
+//! Wakes futures that poll again after a delay from one shared thread, so
+//! waiting does not cost a thread per poll.
+
+use std::{
+    mem,
+    sync::{Condvar, Mutex, MutexGuard, Once, PoisonError},
+    task::Waker,
+    thread,
+    time::Instant,
+};
+
+static PENDING: Mutex<Vec<(Instant, Waker)>> = Mutex::new(Vec::new());
+static CHANGED: Condvar = Condvar::new();
+static START: Once = Once::new();
+
+fn pending_lock() -> MutexGuard<'static, Vec<(Instant, Waker)>> {
+    PENDING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Wakes `waker` once `deadline` has passed. A waker already waiting is
+/// woken at the earlier of its two deadlines.
+pub(crate) fn wake_at(deadline: Instant, waker: Waker) {
+    START.call_once(|| {
+        thread::Builder::new()
+            .name("srt-rs timer".to_string())
+            .spawn(run)
+            .expect("fail to spawn timer thread");
+    });
+    let mut pending = pending_lock();
+    match pending
+        .iter_mut()
+        .find(|(_, other)| other.will_wake(&waker))
+    {
+        Some((at, _)) => *at = (*at).min(deadline),
+        None => pending.push((deadline, waker)),
+    }
+    CHANGED.notify_one();
+}
+
+fn run() {
+    let mut pending = pending_lock();
+    loop {
+        let now = Instant::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = mem::take(&mut *pending)
+            .into_iter()
+            .partition(|(at, _)| *at <= now);
+        *pending = waiting;
+        if !due.is_empty() {
+            // Woken tasks may poll at once and schedule again.
+            drop(pending);
+            for (_, waker) in due {
+                waker.wake();
+            }
+            pending = pending_lock();
+            continue;
+        }
+        pending = match pending.iter().map(|(at, _)| *at).min() {
+            Some(next) => {
+                CHANGED
+                    .wait_timeout(pending, next - now)
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .0
+            }
+            None => CHANGED
+                .wait(pending)
+                .unwrap_or_else(PoisonError::into_inner),
+        };
+    }
+}
@@ -0,0 +1,106 @@
+//! Traits over the async socket types, so code built on them can be tested
+//! against the in-memory `Mock*` types, with the `testing` feature, instead
+//! of real connections.
+
+use crate::{error::SrtError, SrtAsyncBuilder, SrtAsyncListener, SrtAsyncStream};
+
+use futures::{
+    future::{self, BoxFuture, FutureExt},
+    io::{AsyncRead, AsyncWrite},
+};
+
+use std::net::SocketAddr;
+
+#[cfg(feature = "testing")]
+mod mock;
+
+#[cfg(feature = "testing")]
+pub use mock::{MockConnector, MockListener, MockStream, MockWrites};
+
+type Result<T> = std::result::Result<T, SrtError>;
+
+/// A connection; every write is sent as one message.
+pub trait Stream: AsyncRead + AsyncWrite + Unpin + Send {
+    fn local_addr(&self) -> Result<SocketAddr>;
+    fn peer_addr(&self) -> Result<SocketAddr>;
+    fn stream_id(&self) -> Result<String>;
+}
+
+pub trait Listener: Send + Sync {
+    type Stream: Stream;
+    fn accept(&self) -> BoxFuture<'_, Result<(Self::Stream, SocketAddr)>>;
+    fn local_addr(&self) -> Result<SocketAddr>;
+}
+
+/// Opens connections; rejections fail with `SrtErrorKind::ConnRej`.
+pub trait Connector: Send + Sync {
+    type Stream: Stream;
+    fn connect(&self, remote: SocketAddr) -> BoxFuture<'_, Result<Self::Stream>>;
+}
+
+/// Totals since the connection was made.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StreamStats {
+    pub packets_sent: i64,
+    pub packets_received: i64,
+    pub packets_lost: i64,
+    pub packets_retransmitted: i64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub rtt_ms: f64,
+}
+
+pub trait Stats {
+    fn stats(&self) -> Result<StreamStats>;
+}
+
+impl Stream for SrtAsyncStream {
+    fn local_addr(&self) -> Result<SocketAddr> {
+        SrtAsyncStream::local_addr(self)
+    }
+    fn peer_addr(&self) -> Result<SocketAddr> {
+        SrtAsyncStream::peer_addr(self)
+    }
+    fn stream_id(&self) -> Result<String> {
+        self.get_stream_id()
+    }
+}
+
+impl Stats for SrtAsyncStream {
+    fn stats(&self) -> Result<StreamStats> {
+        let stats = self.socket.bistats_total()?;
+        Ok(StreamStats {
+            packets_sent: stats.pktSentTotal,
+            packets_received: stats.pktRecvTotal,
+            packets_lost: stats.pktRcvLossTotal as i64,
+            packets_retransmitted: stats.pktRetransTotal as i64,
+            bytes_sent: stats.byteSentTotal,
+            bytes_received: stats.byteRecvTotal,
+            rtt_ms: stats.msRTT,
+        })
+    }
+}
+
+impl Listener for SrtAsyncListener {
+    type Stream = SrtAsyncStream;
+    fn accept(&self) -> BoxFuture<'_, Result<(SrtAsyncStream, SocketAddr)>> {
+        SrtAsyncListener::accept(self).boxed()
+    }
+    fn local_addr(&self) -> Result<SocketAddr> {
+        SrtAsyncListener::local_addr(self)
+    }
+}
+
+/// Every connection gets the builder's options.
+impl Connector for SrtAsyncBuilder {
+    type Stream = SrtAsyncStream;
+    fn connect(&self, remote: SocketAddr) -> BoxFuture<'_, Result<SrtAsyncStream>> {
+        let builder = SrtAsyncBuilder {
+            opt_vec: self.opt_vec.clone(),
+        };
+        match builder.connect(remote) {
+            Ok(connecting) => connecting.boxed(),
+            Err(e) => future::ready(Err(e)).boxed(),
+        }
+    }
+}
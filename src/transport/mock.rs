@@ -0,0 +1,291 @@
+use super::{Connector, Listener, Result, Stats, Stream, StreamStats};
+use crate::{
+    error::{SrtError, SrtErrorKind},
+    wake_after,
+};
+
+use futures::{
+    future::{self, BoxFuture, FutureExt},
+    io::{AsyncRead, AsyncWrite},
+    task::{Context, Poll},
+};
+
+use std::{
+    collections::VecDeque,
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+// Ready once `until` has passed.
+fn poll_delay(until: Instant, cx: &mut Context<'_>) -> Poll<()> {
+    let now = Instant::now();
+    if now >= until {
+        Poll::Ready(())
+    } else {
+        wake_after(cx, until - now);
+        Poll::Pending
+    }
+}
+
+enum Step {
+    Message(Vec<u8>),
+    Delay(Duration),
+    Disconnect(SrtError),
+}
+
+/// A scripted connection. Reads return the scripted messages in order, with
+/// the scripted pauses between them, and end with a disconnect or, once the
+/// script runs out, with end of stream. Writes are recorded.
+pub struct MockStream {
+    script: VecDeque<Step>,
+    delay_until: Option<Instant>,
+    broken: Option<SrtError>,
+    local_addr: SocketAddr,
+    peer_addr: SocketAddr,
+    stream_id: String,
+    written: Arc<Mutex<Vec<Vec<u8>>>>,
+    stats: StreamStats,
+}
+
+impl Default for MockStream {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MockStream {
+    pub fn new() -> Self {
+        Self {
+            script: VecDeque::new(),
+            delay_until: None,
+            broken: None,
+            local_addr: SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+            peer_addr: SocketAddr::from((Ipv4Addr::LOCALHOST, 0)),
+            stream_id: String::new(),
+            written: Arc::default(),
+            stats: StreamStats::default(),
+        }
+    }
+    pub fn message(mut self, payload: impl Into<Vec<u8>>) -> Self {
+        self.script.push_back(Step::Message(payload.into()));
+        self
+    }
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.script.push_back(Step::Delay(delay));
+        self
+    }
+    /// Reads and writes fail with `error` from this point of the script on,
+    /// e.g. `SrtErrorKind::ConnLost`.
+    pub fn disconnect(mut self, error: impl Into<SrtError>) -> Self {
+        self.script.push_back(Step::Disconnect(error.into()));
+        self
+    }
+    pub fn with_addrs(mut self, local: SocketAddr, peer: SocketAddr) -> Self {
+        self.local_addr = local;
+        self.peer_addr = peer;
+        self
+    }
+    pub fn with_stream_id(mut self, id: impl Into<String>) -> Self {
+        self.stream_id = id.into();
+        self
+    }
+    /// Starting values; the packet and byte counters grow with reads and
+    /// writes.
+    pub fn with_stats(mut self, stats: StreamStats) -> Self {
+        self.stats = stats;
+        self
+    }
+    /// The messages written so far, still readable after the stream has been
+    /// moved into the code under test.
+    pub fn written(&self) -> MockWrites {
+        MockWrites(self.written.clone())
+    }
+}
+
+#[derive(Clone)]
+pub struct MockWrites(Arc<Mutex<Vec<Vec<u8>>>>);
+
+impl MockWrites {
+    pub fn messages(&self) -> Vec<Vec<u8>> {
+        lock(&self.0).clone()
+    }
+}
+
+impl Stream for MockStream {
+    fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.local_addr)
+    }
+    fn peer_addr(&self) -> Result<SocketAddr> {
+        Ok(self.peer_addr)
+    }
+    fn stream_id(&self) -> Result<String> {
+        Ok(self.stream_id.clone())
+    }
+}
+
+impl Stats for MockStream {
+    fn stats(&self) -> Result<StreamStats> {
+        Ok(self.stats)
+    }
+}
+
+impl AsyncRead for MockStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(e) = &this.broken {
+                return Poll::Ready(Err(e.clone().into()));
+            }
+            if let Some(until) = this.delay_until {
+                if poll_delay(until, cx).is_pending() {
+                    return Poll::Pending;
+                }
+                this.delay_until = None;
+            }
+            match this.script.pop_front() {
+                Some(Step::Message(payload)) => {
+                    // Like a message API read, a short buffer truncates.
+                    let len = payload.len().min(buf.len());
+                    buf[..len].copy_from_slice(&payload[..len]);
+                    this.stats.packets_received += 1;
+                    this.stats.bytes_received += len as u64;
+                    return Poll::Ready(Ok(len));
+                }
+                Some(Step::Delay(delay)) => this.delay_until = Some(Instant::now() + delay),
+                Some(Step::Disconnect(e)) => this.broken = Some(e),
+                None => return Poll::Ready(Ok(0)),
+            }
+        }
+    }
+}
+
+impl AsyncWrite for MockStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        // A scripted disconnect right at the front applies to writes too.
+        if let Some(Step::Disconnect(e)) = this.script.front() {
+            this.broken = Some(e.clone());
+        }
+        if let Some(e) = &this.broken {
+            return Poll::Ready(Err(e.clone().into()));
+        }
+        lock(&this.written).push(buf.to_vec());
+        this.stats.packets_sent += 1;
+        this.stats.bytes_sent += buf.len() as u64;
+        Poll::Ready(Ok(buf.len()))
+    }
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.get_mut().broken = Some(SrtErrorKind::Closed.into());
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Hands out scripted connections, each after an optional delay. Once they
+/// are used up, accepting fails with `SrtErrorKind::Closed`.
+pub struct MockListener {
+    local_addr: SocketAddr,
+    incoming: Mutex<VecDeque<(Duration, MockStream)>>,
+}
+
+impl MockListener {
+    pub fn new(local_addr: SocketAddr) -> Self {
+        Self {
+            local_addr,
+            incoming: Mutex::default(),
+        }
+    }
+    pub fn incoming(self, stream: MockStream) -> Self {
+        self.incoming_after(Duration::from_secs(0), stream)
+    }
+    pub fn incoming_after(self, delay: Duration, stream: MockStream) -> Self {
+        lock(&self.incoming).push_back((delay, stream));
+        self
+    }
+}
+
+impl Listener for MockListener {
+    type Stream = MockStream;
+    fn accept(&self) -> BoxFuture<'_, Result<(MockStream, SocketAddr)>> {
+        let next = lock(&self.incoming).pop_front();
+        async move {
+            let (delay, stream) = next.ok_or(SrtError::from(SrtErrorKind::Closed))?;
+            let until = Instant::now() + delay;
+            future::poll_fn(|cx| poll_delay(until, cx)).await;
+            let peer = stream.peer_addr;
+            Ok((stream, peer))
+        }
+        .boxed()
+    }
+    fn local_addr(&self) -> Result<SocketAddr> {
+        Ok(self.local_addr)
+    }
+}
+
+/// Answers connection attempts with scripted outcomes, in order. Once they
+/// are used up, connecting fails with `SrtErrorKind::NoServer`.
+#[derive(Default)]
+pub struct MockConnector {
+    outcomes: Mutex<VecDeque<(Duration, Result<MockStream>)>>,
+    attempts: Mutex<Vec<SocketAddr>>,
+}
+
+impl MockConnector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn accept(self, stream: MockStream) -> Self {
+        self.outcome(Duration::from_secs(0), Ok(stream))
+    }
+    /// The next attempt is rejected, e.g. with `SrtRejectReason::BadSecret`.
+    pub fn reject(self, reason: crate::error::SrtRejectReason) -> Self {
+        self.outcome(
+            Duration::from_secs(0),
+            Err(SrtErrorKind::ConnRej(reason).into()),
+        )
+    }
+    /// The next attempt ends after `delay`; use `SrtErrorKind::NoServer` for a
+    /// timeout.
+    pub fn outcome(self, delay: Duration, outcome: Result<MockStream>) -> Self {
+        lock(&self.outcomes).push_back((delay, outcome));
+        self
+    }
+    /// Where connections were attempted to, in order.
+    pub fn attempts(&self) -> Vec<SocketAddr> {
+        lock(&self.attempts).clone()
+    }
+}
+
+impl Connector for MockConnector {
+    type Stream = MockStream;
+    fn connect(&self, remote: SocketAddr) -> BoxFuture<'_, Result<MockStream>> {
+        lock(&self.attempts).push(remote);
+        let next = lock(&self.outcomes).pop_front();
+        async move {
+            let (delay, outcome) = next.ok_or(SrtError::from(SrtErrorKind::NoServer))?;
+            let until = Instant::now() + delay;
+            future::poll_fn(|cx| poll_delay(until, cx)).await;
+            let mut stream = outcome?;
+            stream.peer_addr = remote;
+            Ok(stream)
+        }
+        .boxed()
+    }
+}
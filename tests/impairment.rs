@@ -0,0 +1,195 @@
+use srt_rs as srt;
+
+use srt::{
+    testing::{Direction, Impairment, ImpairmentProxy},
+    FecArq, FecConfig, PacketFilter, SrtBuilder, SrtListener, SrtSocketStatus, SrtStream,
+};
+
+use std::{
+    io::{Read, Write},
+    thread,
+    time::{Duration, Instant},
+};
+
+const PAYLOAD_SIZE: usize = 1316;
+
+struct Link {
+    proxy: ImpairmentProxy,
+    caller: SrtStream,
+    peer: SrtStream,
+    listen: SrtListener,
+}
+
+impl Link {
+    fn close(self) {
+        assert!(self.caller.close().is_ok());
+        assert!(self.peer.close().is_ok());
+        assert!(self.listen.close().is_ok());
+        drop(self.proxy);
+    }
+}
+
+fn connect(listener: SrtBuilder, caller: SrtBuilder, impairment: Impairment, seed: u64) -> Link {
+    let listen = listener.listen("127.0.0.1:0", 1).expect("fail listen()");
+    let addr = listen.local_addr().expect("fail local_addr()");
+    let proxy = ImpairmentProxy::start(addr, impairment, seed).expect("fail start()");
+    let caller = caller.connect(proxy.addr()).expect("fail connect()");
+    let (peer, _peer_addr) = listen.accept().expect("fail accept()");
+    Link {
+        proxy,
+        caller,
+        peer,
+        listen,
+    }
+}
+
+// Sends `count` numbered payloads at about 10 Mbit/s from another thread and
+// returns how many arrived.
+fn transfer(link: Link, count: u32) -> (Link, u32) {
+    let Link {
+        proxy,
+        mut caller,
+        mut peer,
+        listen,
+    } = link;
+    let sender = thread::spawn(move || {
+        let mut payload = [0; PAYLOAD_SIZE];
+        for i in 0..count {
+            payload[..4].copy_from_slice(&i.to_be_bytes());
+            caller.write_all(&payload).expect("fail write()");
+            thread::sleep(Duration::from_millis(1));
+        }
+        caller
+    });
+    peer.set_receive_timeout(2000)
+        .expect("fail set_receive_timeout()");
+    let mut buf = [0; PAYLOAD_SIZE];
+    let mut received = 0;
+    while received < count {
+        match peer.read(&mut buf) {
+            Ok(PAYLOAD_SIZE) => received += 1,
+            _ => break,
+        }
+    }
+    let caller = sender.join().expect("sender thread panicked");
+    let link = Link {
+        proxy,
+        caller,
+        peer,
+        listen,
+    };
+    (link, received)
+}
+
+#[test]
+fn test_retransmission_recovers_loss() {
+    let impairment = Impairment {
+        loss: 0.05,
+        ..Impairment::default()
+    };
+    let link = connect(
+        srt::builder().set_receive_latency(400),
+        srt::builder().set_peer_latency(400),
+        impairment,
+        1,
+    );
+    let (link, received) = transfer(link, 500);
+    assert_eq!(received, 500);
+    assert!(link.proxy.stats(Direction::Upstream).lost() > 0);
+    let stats = link.caller.bistats().expect("fail bistats()");
+    assert!(stats.pktRetransTotal > 0);
+    link.close();
+}
+
+#[test]
+fn test_too_late_packets_are_dropped() {
+    let link = connect(
+        srt::builder().set_receive_latency(40),
+        srt::builder().set_peer_latency(40),
+        Impairment::default(),
+        2,
+    );
+    link.proxy.set_impairment(
+        Direction::Upstream,
+        Impairment {
+            loss: 0.05,
+            jitter: Duration::from_millis(10),
+            ..Impairment::default()
+        },
+    );
+    // Loss reports crawl back, so retransmissions miss the 40 ms latency.
+    link.proxy.set_impairment(
+        Direction::Downstream,
+        Impairment {
+            delay: Duration::from_millis(100),
+            ..Impairment::default()
+        },
+    );
+    let (link, received) = transfer(link, 500);
+    assert!(received < 500);
+    let stats = link.peer.bistats().expect("fail bistats()");
+    assert!(stats.pktRcvDropTotal > 0);
+    link.close();
+}
+
+#[test]
+fn test_fec_recovers_loss() {
+    let fec = || {
+        PacketFilter::Fec(
+            FecConfig::new(10)
+                .and_then(|fec| fec.set_rows(1))
+                .expect("invalid FEC configuration")
+                .set_arq(FecArq::Never),
+        )
+    };
+    let impairment = Impairment {
+        loss: 0.01,
+        ..Impairment::default()
+    };
+    let link = connect(
+        srt::builder()
+            .set_receive_latency(200)
+            .set_packet_filter(fec()),
+        srt::builder()
+            .set_peer_latency(200)
+            .set_packet_filter(fec()),
+        impairment,
+        3,
+    );
+    let (link, _received) = transfer(link, 1000);
+    let stats = link.peer.filter_stats().expect("fail filter_stats()");
+    assert!(stats.supplied > 0);
+    link.close();
+}
+
+#[test]
+fn test_connection_loss_is_detected() {
+    let link = connect(
+        srt::builder().set_peer_idle_timeout(1000),
+        srt::builder().set_peer_idle_timeout(1000),
+        Impairment::default(),
+        4,
+    );
+    let blackhole = Impairment {
+        loss: 1.0,
+        ..Impairment::default()
+    };
+    link.proxy.set_impairment(Direction::Upstream, blackhole);
+    link.proxy.set_impairment(Direction::Downstream, blackhole);
+    let started = Instant::now();
+    loop {
+        let state = link
+            .caller
+            .get_socket_state()
+            .expect("fail get_socket_state()");
+        if matches!(state, SrtSocketStatus::Broken) {
+            break;
+        }
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "loss not detected"
+        );
+        thread::sleep(Duration::from_millis(50));
+    }
+    link.close();
+}